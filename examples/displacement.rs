@@ -0,0 +1,112 @@
+//! Example displacing a flat grid on the GPU with an animated height texture.
+//! `cargo run --example displacement --features=displacement,bevy/dynamic_linking`
+use std::f32::consts::PI;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    camera::visibility::NoFrustumCulling,
+    color::palettes::css::WHITE,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_heightmap::*;
+
+pub const SCALE: f32 = 1024.;
+pub const HEIGHT: f32 = 32.;
+pub const THETA: f32 = PI / 8.;
+pub const FOV: f32 = PI / 4.;
+pub const TEXTURE_SIZE: u32 = 256;
+pub fn y_offset(z: f32) -> f32 {
+    THETA.tan() * z
+}
+
+#[derive(Resource)]
+struct HeightTexture(Handle<Image>);
+
+fn setup(
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<HeightMapMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut commands: Commands,
+) {
+    let height_texture = images.add(Image::new_fill(
+        Extent3d {
+            width: TEXTURE_SIZE,
+            height: TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 16],
+        TextureFormat::Rgba32Float,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    ));
+    commands.insert_resource(HeightTexture(height_texture.clone()));
+    commands.spawn((
+        Name::new("Terrain"),
+        Mesh3d(meshes.add(bevy_heightmap::MeshBuilder::grid(UVec2::splat(TEXTURE_SIZE)).build())),
+        MeshMaterial3d(materials.add(HeightMapMaterial {
+            base: StandardMaterial {
+                base_color: Color::WHITE,
+                ..default()
+            },
+            extension: HeightMapDisplacement::new(height_texture),
+        })),
+        // Bounds are computed from the flat grid, before displacement.
+        NoFrustumCulling,
+        Transform {
+            scale: Vec2::splat(SCALE).extend(HEIGHT),
+            ..default()
+        },
+    ));
+    let default_height = 1500.;
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: FOV,
+            near: 0.1,
+            far: 2000.,
+            ..default()
+        }),
+        Transform::from_xyz(0.0, -y_offset(default_height), default_height)
+            .with_rotation(Quat::from_axis_angle(Vec3::X, THETA)),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            color: WHITE.into(),
+            illuminance: 4500.,
+            shadow_maps_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, default_height)
+            .with_rotation(Quat::from_axis_angle(Vec3::ONE, -PI / 6.)),
+    ));
+}
+
+/// Writes animated waves into the height texture without touching the mesh.
+fn animate_heights(
+    time: Res<Time>,
+    height_texture: Res<HeightTexture>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(mut image) = images.get_mut(&height_texture.0) else {
+        return;
+    };
+    let t = time.elapsed_secs();
+    for y in 0..TEXTURE_SIZE {
+        for x in 0..TEXTURE_SIZE {
+            let p = UVec2::new(x, y).as_vec2() / TEXTURE_SIZE as f32;
+            let h = ((20. * p.x + t).sin() + (20. * p.y + t).sin()) / 4. + 0.5;
+            image
+                .set_color_at(x, y, Color::linear_rgba(h, h, h, 1.))
+                .unwrap();
+        }
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins((DefaultPlugins, HeightMapPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, animate_heights)
+        .run();
+}
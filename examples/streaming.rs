@@ -0,0 +1,72 @@
+//! Example streaming procedural terrain chunks around a moving camera.
+//! `cargo run --example streaming --features=bevy/dynamic_linking`
+use std::f32::consts::PI;
+
+use bevy::{color::palettes::css::WHITE, prelude::*};
+use bevy_heightmap::*;
+
+pub const SPEED: f32 = 200.;
+pub const HEIGHT: f32 = 400.;
+pub const THETA: f32 = PI / 4.;
+
+#[derive(Resource)]
+struct ChunkMaterial(Handle<StandardMaterial>);
+
+fn setup(mut materials: ResMut<Assets<StandardMaterial>>, mut commands: Commands) {
+    commands.insert_resource(ChunkMaterial(materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        perceptual_roughness: 1.,
+        ..default()
+    })));
+    let camera = commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 0.0, HEIGHT)
+                .with_rotation(Quat::from_axis_angle(Vec3::X, THETA)),
+        ))
+        .id();
+    let mut streamer = TerrainStreamer::new(
+        ValueFunctionHeightMap(|p: Vec2| {
+            ((p.x / 150.).sin() + (p.y / 110.).sin() + (p.x / 37. + p.y / 53.).sin() / 4.) * 0.5
+        }),
+        camera,
+    );
+    streamer.height_scale = 40.;
+    commands.spawn((Name::new("Terrain"), streamer));
+    commands.spawn((
+        DirectionalLight {
+            color: WHITE.into(),
+            illuminance: 4500.,
+            shadow_maps_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, HEIGHT)
+            .with_rotation(Quat::from_axis_angle(Vec3::ONE, -PI / 6.)),
+    ));
+}
+
+/// Gives newly streamed chunks a material.
+fn add_chunk_material(
+    add: On<Add, TerrainChunk>,
+    material: Res<ChunkMaterial>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(add.entity)
+        .insert(MeshMaterial3d(material.0.clone()));
+}
+
+fn move_camera(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera3d>>) {
+    for mut transform in cameras.iter_mut() {
+        transform.translation.y += SPEED * time.delta_secs();
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins((DefaultPlugins, HeightMapPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, move_camera)
+        .add_observer(add_chunk_material)
+        .run();
+}
@@ -10,12 +10,6 @@ use bevy_egui::EguiPlugin;
 use bevy_heightmap::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
-#[derive(Component, Default)]
-struct Terrain {}
-impl Terrain {
-    fn update() {}
-}
-
 pub const SCALE: f32 = 1024.;
 pub const HEIGHT: f32 = 32.;
 pub const THETA: f32 = PI / 8.;
@@ -31,14 +25,12 @@ fn setup(
     mut commands: Commands,
 ) {
     let texture: Handle<Image> = asset_server.load("textures/uv.png");
-    let mesh: Handle<Mesh> = meshes.add(
-        ValueFunctionHeightMap(|p: Vec2| ((20. * p.x).sin() + (20. * p.y).sin()) / 2.)
-            .build_mesh(UVec2::new(128, 128)),
-    );
     commands.spawn((
         Name::new("Terrain"),
-        Terrain::default(),
-        Mesh3d(mesh),
+        Terrain::from_heightmap(ValueFunctionHeightMap(|p: Vec2| {
+            ((20. * p.x).sin() + (20. * p.y).sin()) / 2.
+        }))
+        .with_resolution(UVec2::new(128, 128)),
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::WHITE,
             base_color_texture: Some(texture),
@@ -111,6 +103,5 @@ fn main() {
         WorldInspectorPlugin::default(),
     ))
     .add_systems(Startup, setup)
-    .add_systems(Update, Terrain::update)
     .run();
 }
@@ -5,38 +5,134 @@ use bevy::{
         CompressedImageFormats, Image, ImageFormat, ImageFormatSetting, ImageLoaderSettings,
         ImageType, IntoDynamicImageError, TextureError,
     },
+    math::{FloatExt, Rect, UVec2, Vec2},
     mesh::Mesh,
     reflect::TypePath,
     render::renderer::RenderDevice,
 };
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::HeightMap;
-use crate::image::ImageBufferHeightMap;
+use crate::image::{HeightEncoding, Holes, ImageBufferHeightMap};
+use crate::{HeightGrid, MeshBuilder, Orientation};
+
+/// Settings for the [`HeightMapLoader`].
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct HeightMapLoaderSettings {
+    /// Settings used to decode the image.
+    pub image: ImageLoaderSettings,
+    /// Size of the mesh along x and y in world units, centered on the origin.
+    /// Defaults to fitting the image in the unit square while preserving its aspect ratio.
+    pub extents: Option<Vec2>,
+    /// Up axis of the loaded mesh.
+    pub orientation: Orientation,
+    /// How heights are stored in the image's pixels.
+    pub encoding: HeightEncoding,
+    /// Pixels left out of the mesh, and NaN in the `HeightGrid`.
+    pub holes: Holes,
+    /// Remapping of the sampled heights, applied before the mesh is built.
+    pub remap: HeightRemap,
+}
+impl HeightMapLoaderSettings {
+    /// Area covered by the mesh of an image of `size`.
+    pub fn rect(&self, size: UVec2) -> Rect {
+        match self.extents {
+            Some(extents) => Rect::from_center_size(Vec2::ZERO, extents),
+            None => MeshBuilder::aspect_rect(size),
+        }
+    }
+}
+
+/// Conversion of the heights decoded from an image, between `0` and `1` for single channels, to mesh heights.
+/// The `HeightGrid` labeled asset holds the remapped heights.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{asset_loader::HeightRemap, *};
+/// let mut grid = HeightGrid::new(UVec2::new(3, 1), vec![0.2, 0.3, 0.4]);
+/// HeightRemap::Curve(vec![Vec2::new(0., 0.), Vec2::new(0.3, 0.1), Vec2::new(1., 1.)]).apply(&mut grid);
+/// assert_eq!(grid.heights[1], 0.1);
+/// HeightRemap::Stretch.apply(&mut grid);
+/// assert_eq!(grid.heights[2], 1.);
+/// ```
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub enum HeightRemap {
+    /// Heights are used as sampled.
+    #[default]
+    None,
+    /// Stretches the range of heights used by the image to `[0, 1]`.
+    Stretch,
+    /// Maps heights through a piecewise linear curve of `(input, output)` points sorted by input.
+    /// Heights outside of the curve take the output of its first or last point.
+    Curve(Vec<Vec2>),
+    /// Heights are absolute elevations, with a sample of `1` at `max` meters,
+    /// shifted so `sea_level` is at zero. The loader reads 8-bit RGBA images, so an image storing
+    /// whole meters in a channel has a `max` of `255`. Load 16-bit and float rasters with [`crate::gis`].
+    Meters { max: f32, sea_level: f32 },
+}
+impl HeightRemap {
+    pub fn apply(&self, grid: &mut HeightGrid) {
+        match self {
+            Self::None => {}
+            Self::Stretch => {
+                let stats = grid.statistics();
+                for h in &mut grid.heights {
+                    *h = stats.normalize(*h);
+                }
+            }
+            Self::Curve(points) => {
+                for h in &mut grid.heights {
+                    *h = Self::curve(points, *h);
+                }
+            }
+            Self::Meters { max, sea_level } => {
+                for h in &mut grid.heights {
+                    *h = *h * max - sea_level;
+                }
+            }
+        }
+    }
+
+    fn curve(points: &[Vec2], h: f32) -> f32 {
+        if h.is_nan() {
+            // Holes stay holes.
+            return h;
+        }
+        let i = points.partition_point(|point| point.x < h);
+        match (points.get(i.wrapping_sub(1)), points.get(i)) {
+            (Some(a), Some(b)) => a.y.lerp(b.y, (h - a.x) / (b.x - a.x)),
+            (Some(a), None) => a.y,
+            (None, Some(b)) => b.y,
+            (None, None) => h,
+        }
+    }
+}
 
 /// Loader for images that can be read by the `image` crate.
+///
+/// The sampled heights are also available as a [`HeightGrid`] under the `HeightGrid` label.
 #[derive(Clone, TypePath)]
 pub struct HeightMapLoader {
     supported_compressed_formats: CompressedImageFormats,
 }
 impl HeightMapLoader {
     pub const EXTENSIONS: &'static [&'static str] = &["hmp.png"];
+    pub const HEIGHT_GRID_LABEL: &'static str = "HeightGrid";
 }
 
 impl AssetLoader for HeightMapLoader {
     type Asset = Mesh;
-    type Settings = ImageLoaderSettings;
+    type Settings = HeightMapLoaderSettings;
     type Error = HeightMapLoaderError;
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        settings: &ImageLoaderSettings,
+        settings: &HeightMapLoaderSettings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Mesh, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let image_type = match settings.format {
+        let image_type = match settings.image.format {
             ImageFormatSetting::FromExtension => {
                 // use the file extension for the image type
                 let ext = load_context
@@ -66,16 +162,26 @@ impl AssetLoader for HeightMapLoader {
             &bytes,
             image_type,
             self.supported_compressed_formats,
-            settings.is_srgb,
-            settings.sampler.clone(),
-            settings.asset_usage,
+            settings.image.is_srgb,
+            settings.image.sampler.clone(),
+            settings.image.asset_usage,
         )
         .map_err(|err| HeightMapFileError {
             error: err,
             path: format!("{}", load_context.path().path().display()),
         })?;
-        let image_heightmap = ImageBufferHeightMap::try_from_image(image.clone())?;
-        Ok(image_heightmap.build_mesh(image.size()))
+        let size = image.size();
+        let image_heightmap = ImageBufferHeightMap::try_from_image(image)?
+            .with_encoding(settings.encoding)
+            .with_holes(settings.holes);
+        let mut grid = HeightGrid::from_heightmap(&image_heightmap, size);
+        settings.remap.apply(&mut grid);
+        let mesh = grid
+            .mesh_builder(settings.rect(size))
+            .with_orientation(settings.orientation)
+            .build();
+        load_context.add_labeled_asset(Self::HEIGHT_GRID_LABEL.to_string(), grid);
+        Ok(mesh)
     }
 
     fn extensions(&self) -> &[&str] {
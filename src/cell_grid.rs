@@ -0,0 +1,177 @@
+use std::f32::consts::{FRAC_PI_3, FRAC_PI_6};
+
+use bevy::{
+    math::{Rect, UVec2, Vec2},
+    mesh::{MeshVertexAttribute, VertexFormat},
+};
+
+use crate::MeshBuilder;
+
+/// Vertex attribute holding the index of the cell a vertex belongs to,
+/// `MeshBuilder::index(column, row, cells.x)`.
+///
+/// Every vertex belongs to a single cell, so the cell of a triangle is the cell of any of its vertices.
+pub const ATTRIBUTE_CELL_ID: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_CellId", 1_812_734_421, VertexFormat::Uint32);
+
+impl MeshBuilder {
+    /// Compute a mesh of pointy-top hexagons in rows, with odd rows shifted right by half a cell.
+    ///
+    /// Each hexagon is a fan of six triangles around its center, with `radius` from center to corner.
+    /// Cells have their own vertices, tagged with [`ATTRIBUTE_CELL_ID`].
+    /// The heightmap spans the bounding rectangle of the cells, centered on the origin.
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_heightmap::{cell_grid::*, *};
+    /// let mut builder = bevy_heightmap::MeshBuilder::hex_grid(UVec2::new(8, 6), 1.);
+    /// builder.update_z_positions(&ValueFunctionHeightMap(|p: Vec2| p.length()));
+    /// let mesh = builder.build();
+    /// assert_eq!(mesh.count_vertices(), 8 * 6 * 7);
+    /// assert!(mesh.attribute(ATTRIBUTE_CELL_ID).is_some());
+    /// ```
+    pub fn hex_grid(cells: UVec2, radius: f32) -> Self {
+        let width = 3f32.sqrt() * radius;
+        let center = |column: u32, row: u32| {
+            Vec2::new(
+                width * (column as f32 + 0.5 * (row % 2) as f32),
+                1.5 * radius * row as f32,
+            )
+        };
+        let corners: [Vec2; 6] =
+            std::array::from_fn(|i| radius * Vec2::from_angle(FRAC_PI_6 + FRAC_PI_3 * i as f32));
+        let mut builder = Self::cells(cells, 7, 18);
+        for row in 0..cells.y {
+            for column in 0..cells.x {
+                let start = builder.positions.len() as u32;
+                let center = center(column, row);
+                builder.push_cell_vertices(
+                    MeshBuilder::index(column, row, cells.x),
+                    std::iter::once(center).chain(corners.map(|corner| center + corner)),
+                );
+                for i in 0..6 {
+                    builder
+                        .indices
+                        .extend([start, start + 1 + i, start + 1 + (i + 1) % 6]);
+                }
+            }
+        }
+        builder.finish_cells();
+        builder
+    }
+
+    /// Compute a mesh of equilateral triangles with sides of length `side`,
+    /// in rows alternating between upward and downward pointing triangles.
+    ///
+    /// Cells have their own vertices, tagged with [`ATTRIBUTE_CELL_ID`].
+    /// The heightmap spans the bounding rectangle of the cells, centered on the origin.
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_heightmap::{cell_grid::*, *};
+    /// let mut builder = bevy_heightmap::MeshBuilder::triangle_grid(UVec2::new(8, 4), 1.);
+    /// builder.update_z_positions(&ValueFunctionHeightMap(|p: Vec2| p.x));
+    /// let mesh = builder.build();
+    /// assert_eq!(mesh.count_vertices(), 8 * 4 * 3);
+    /// assert!(mesh.attribute(ATTRIBUTE_CELL_ID).is_some());
+    /// ```
+    pub fn triangle_grid(cells: UVec2, side: f32) -> Self {
+        let height = 3f32.sqrt() / 2. * side;
+        let mut builder = Self::cells(cells, 3, 3);
+        for row in 0..cells.y {
+            let y = height * row as f32;
+            let offset = 0.5 * side * (row % 2) as f32;
+            for column in 0..cells.x {
+                let start = builder.positions.len() as u32;
+                let x = offset + side * (column / 2) as f32;
+                let vertices = if column % 2 == 0 {
+                    [
+                        Vec2::new(x, y),
+                        Vec2::new(x + side, y),
+                        Vec2::new(x + 0.5 * side, y + height),
+                    ]
+                } else {
+                    [
+                        Vec2::new(x + side, y),
+                        Vec2::new(x + 1.5 * side, y + height),
+                        Vec2::new(x + 0.5 * side, y + height),
+                    ]
+                };
+                builder.push_cell_vertices(MeshBuilder::index(column, row, cells.x), vertices);
+                builder.indices.extend([start, start + 1, start + 2]);
+            }
+        }
+        builder.finish_cells();
+        builder
+    }
+
+    fn cells(cells: UVec2, vertices_per_cell: usize, indices_per_cell: usize) -> Self {
+        let num_cells = cells.x as usize * cells.y as usize;
+        Self {
+            positions: Vec::with_capacity(num_cells * vertices_per_cell),
+            uvs: Vec::with_capacity(num_cells * vertices_per_cell),
+            normals: Vec::new(),
+            indices: Vec::with_capacity(num_cells * indices_per_cell),
+            cell_ids: Vec::with_capacity(num_cells * vertices_per_cell),
+            ..Self::default()
+        }
+    }
+
+    fn push_cell_vertices(&mut self, cell: u32, vertices: impl IntoIterator<Item = Vec2>) {
+        for vertex in vertices {
+            self.positions.push(vertex.extend(0.).to_array());
+            self.cell_ids.push(cell);
+        }
+    }
+
+    /// Centers the cells on the origin and sets the heightmap rectangle and UVs to their bounds.
+    fn finish_cells(&mut self) {
+        let bounds = self.positions.iter().fold(Rect::EMPTY, |rect, p| {
+            rect.union_point(Vec2::new(p[0], p[1]))
+        });
+        let offset = bounds.center();
+        self.rect = Rect::from_center_size(Vec2::ZERO, bounds.size());
+        for p in self.positions.iter_mut() {
+            let xy = (Vec2::new(p[0], p[1]) - offset) / bounds.size();
+            p[0] -= offset.x;
+            p[1] -= offset.y;
+            self.uvs
+                .push(Self::position_to_uv(&xy.extend(0.).to_array()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec3;
+
+    use super::*;
+
+    #[test]
+    fn triangle_grid_cells_are_equilateral() {
+        let cells = UVec2::new(6, 3);
+        let side = 2.;
+        let builder = MeshBuilder::triangle_grid(cells, side);
+        assert_eq!(builder.indices.len(), 6 * 3 * 3);
+        let bounds = builder.positions.iter().fold(Rect::EMPTY, |rect, p| {
+            rect.union_point(Vec2::new(p[0], p[1]))
+        });
+        assert!(bounds.center().abs_diff_eq(Vec2::ZERO, 1e-5));
+        assert_eq!(builder.rect, bounds);
+        for (cell, triangle) in builder.indices.chunks_exact(3).enumerate() {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(builder.positions[triangle[i] as usize]));
+            for (start, end) in [(a, b), (b, c), (c, a)] {
+                assert!((start.distance(end) - side).abs() < 1e-5);
+            }
+            // Counterclockwise, so the triangle faces up.
+            assert!((b - a).cross(c - a).z > 0.);
+            for &i in triangle {
+                assert_eq!(builder.cell_ids[i as usize], cell as u32);
+            }
+        }
+        // Neighboring cells in a row share an edge.
+        let vertex = |cell: usize, corner: usize| {
+            Vec3::from(builder.positions[builder.indices[3 * cell + corner] as usize])
+        };
+        assert!(vertex(0, 1).abs_diff_eq(vertex(1, 0), 1e-5));
+        assert!(vertex(0, 2).abs_diff_eq(vertex(1, 2), 1e-5));
+    }
+}
@@ -0,0 +1,247 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    math::{Rect, UVec2, Vec2, Vec3},
+    mesh::{Indices, Mesh, PrimitiveTopology},
+    platform::collections::{HashMap, HashSet},
+};
+
+use crate::{HeightGrid, HeightMap, MeshBuilder, Orientation};
+
+/// Isoline of a heightmap at a single level.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contour {
+    pub level: f32,
+    /// Heightmap space points along the line.
+    pub points: Vec<Vec2>,
+    /// Whether the line loops back to its first point, which is not repeated.
+    /// Open lines start and end on the edge of the grid or next to holes.
+    pub closed: bool,
+}
+
+/// Topographic contour lines, extracted with marching squares.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{contour::*, *};
+/// let hill = ValueFunctionHeightMap(|p: Vec2| 1. - 2. * p.length());
+/// let contours = Contours::new(&hill, UVec2::new(65, 65), &[0.25, 0.5, 0.75]);
+/// assert_eq!(contours.lines.len(), 3);
+/// assert!(contours.lines.iter().all(|line| line.closed));
+/// let lines: Mesh = contours.mesh(Rect::new(-50., -50., 50., 50.), 10., 0.1, Orientation::YUp);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contours {
+    pub lines: Vec<Contour>,
+}
+
+impl Contours {
+    /// Contours of `heightmap` sampled at a grid of `size`, at each of `levels`.
+    pub fn new<H: HeightMap + ?Sized>(heightmap: &H, size: UVec2, levels: &[f32]) -> Self {
+        Self::from_grid(&HeightGrid::from_heightmap(heightmap, size), levels)
+    }
+
+    /// Contours every `interval` from `base`, covering the range of the grid's heights.
+    /// Empty unless `interval` is positive.
+    pub fn with_interval(grid: &HeightGrid, base: f32, interval: f32) -> Self {
+        if interval.is_nan() || interval <= 0. {
+            return Self::default();
+        }
+        let (min, max) = grid
+            .heights
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &h| {
+                (min.min(h), max.max(h))
+            });
+        let first = ((min - base) / interval).ceil() as i32;
+        let last = ((max - base) / interval).floor() as i32;
+        let levels: Vec<f32> = (first..=last).map(|i| base + i as f32 * interval).collect();
+        Self::from_grid(grid, &levels)
+    }
+
+    pub fn from_grid(grid: &HeightGrid, levels: &[f32]) -> Self {
+        Self {
+            lines: levels
+                .iter()
+                .flat_map(|&level| isolines(grid, level))
+                .collect(),
+        }
+    }
+
+    /// Lines in the XY plane of a mesh spanning `rect`, at their level multiplied by `height_scale`.
+    pub fn polylines(
+        &self,
+        rect: Rect,
+        height_scale: f32,
+        orientation: Orientation,
+    ) -> Vec<Vec<Vec3>> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let mut points: Vec<Vec3> = line
+                    .points
+                    .iter()
+                    .map(|p| {
+                        let p =
+                            (rect.center() + *p * rect.size()).extend(height_scale * line.level);
+                        Vec3::from(orientation.orient(p.to_array()))
+                    })
+                    .collect();
+                if line.closed {
+                    points.push(points[0]);
+                }
+                points
+            })
+            .collect()
+    }
+
+    /// [`PrimitiveTopology::LineList`] mesh of the lines draped on a terrain mesh spanning `rect`,
+    /// raised by `lift` so they are not hidden by the terrain between grid points.
+    /// Normals point up and UVs match those of the terrain.
+    pub fn mesh(&self, rect: Rect, height_scale: f32, lift: f32, orientation: Orientation) -> Mesh {
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        for line in &self.lines {
+            let start = positions.len() as u32;
+            for p in &line.points {
+                let position =
+                    (rect.center() + *p * rect.size()).extend(height_scale * line.level + lift);
+                positions.push(position.to_array());
+                uvs.push(MeshBuilder::position_to_uv(&p.extend(0.).to_array()));
+            }
+            let end = positions.len() as u32;
+            for i in start + 1..end {
+                indices.extend([i - 1, i]);
+            }
+            if line.closed && end > start {
+                indices.extend([end - 1, start]);
+            }
+        }
+        let normals = vec![[0., 0., 1.]; positions.len()];
+        let mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+            .with_inserted_indices(Indices::U32(indices));
+        orientation.orient_mesh(mesh)
+    }
+}
+
+/// Isolines of a grid at `level`, joined from the segments of each cell.
+fn isolines(grid: &HeightGrid, level: f32) -> Vec<Contour> {
+    let above = |xy: UVec2| grid.get(xy) > level;
+    // Edges are identified by their first grid point, and whether they run along x or y.
+    let edge_id = |xy: UVec2, along_y: bool| 2 * grid.index(xy) + along_y as usize;
+    let mut crossings: HashMap<usize, Vec2> = HashMap::default();
+    let mut links: HashMap<usize, Vec<usize>> = HashMap::default();
+    for y in 0..grid.size.y.saturating_sub(1) {
+        for x in 0..grid.size.x.saturating_sub(1) {
+            let corners =
+                [(0, 0), (1, 0), (1, 1), (0, 1)].map(|(dx, dy)| UVec2::new(x + dx, y + dy));
+            // Lines end at holes, like at the edge of the grid.
+            if corners.iter().any(|&xy| grid.get(xy).is_nan()) {
+                continue;
+            }
+            let states = corners.map(above);
+            if states.iter().all(|&s| s == states[0]) {
+                continue;
+            }
+            // Bottom, right, top and left edges, each from corner i to corner i + 1.
+            let mut crossed = Vec::with_capacity(4);
+            for i in 0..4 {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                if states[i] == states[(i + 1) % 4] {
+                    continue;
+                }
+                let (ha, hb) = (grid.get(a), grid.get(b));
+                let (first, along_y) = (a.min(b), a.x == b.x);
+                let id = edge_id(first, along_y);
+                crossings.entry(id).or_insert_with(|| {
+                    let t = (level - ha) / (hb - ha);
+                    grid.position(a).lerp(grid.position(b), t)
+                });
+                crossed.push(id);
+            }
+            let segments = if crossed.len() == 2 {
+                vec![(crossed[0], crossed[1])]
+            } else {
+                // Saddle: separate the corners whose state differs from the cell's center.
+                let center = corners.iter().map(|&xy| grid.get(xy)).sum::<f32>() / 4. > level;
+                let isolated = if states[0] == center { 1 } else { 0 };
+                // The edges around corner i are edge i - 1 and edge i.
+                [isolated, isolated + 2]
+                    .map(|i| (crossed[(i + 3) % 4], crossed[i]))
+                    .to_vec()
+            };
+            for (a, b) in segments {
+                links.entry(a).or_default().push(b);
+                links.entry(b).or_default().push(a);
+            }
+        }
+    }
+    let mut lines = Vec::new();
+    let mut visited: HashSet<usize> = HashSet::default();
+    let mut trace = |start: usize, visited: &mut HashSet<usize>| {
+        let mut points = vec![crossings[&start]];
+        visited.insert(start);
+        let mut current = start;
+        while let Some(&next) = links[&current].iter().find(|id| !visited.contains(*id)) {
+            visited.insert(next);
+            points.push(crossings[&next]);
+            current = next;
+        }
+        let closed = points.len() > 2 && links[&current].contains(&start);
+        lines.push(Contour {
+            level,
+            points,
+            closed,
+        });
+    };
+    // Open lines first, from their ends on the edge of the grid, then the remaining loops.
+    let mut ids: Vec<usize> = links.keys().copied().collect();
+    ids.sort_unstable();
+    for &id in &ids {
+        if links[&id].len() == 1 && !visited.contains(&id) {
+            trace(id, &mut visited);
+        }
+    }
+    for &id in &ids {
+        if !visited.contains(&id) {
+            trace(id, &mut visited);
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn degenerate_inputs_have_no_contours() {
+        let grid = HeightGrid::new(UVec2::new(2, 2), vec![0., 1., 2., 3.]);
+        for interval in [0., -1., f32::NAN] {
+            assert_eq!(
+                Contours::with_interval(&grid, 0., interval),
+                Contours::default()
+            );
+        }
+        for size in [UVec2::new(0, 3), UVec2::new(3, 0), UVec2::new(1, 3)] {
+            let grid = HeightGrid::flat(size, 0.);
+            assert!(Contours::from_grid(&grid, &[0.5]).lines.is_empty());
+        }
+    }
+
+    #[test]
+    fn lines_end_at_holes() {
+        let hill = crate::ValueFunctionHeightMap(|p: Vec2| 1. - 2. * p.length());
+        let mut grid = HeightGrid::from_heightmap(&hill, UVec2::new(33, 33));
+        // A hole crossed by the contour.
+        *grid.get_mut(UVec2::new(24, 16)) = f32::NAN;
+        let contours = Contours::from_grid(&grid, &[0.5]);
+        assert_eq!(contours.lines.len(), 1);
+        let line = &contours.lines[0];
+        assert!(!line.closed);
+        assert!(line.points.iter().all(|p| p.is_finite()));
+        assert_eq!(Contours::with_interval(&grid, 0., 0.5).lines.len(), 2);
+    }
+}
@@ -0,0 +1,72 @@
+use bevy::{
+    asset::{Asset, Handle},
+    image::Image,
+    pbr::{ExtendedMaterial, MaterialExtension, StandardMaterial},
+    reflect::Reflect,
+    render::render_resource::AsBindGroup,
+    shader::ShaderRef,
+};
+
+/// Standard material whose vertices are displaced on the GPU by a height texture.
+pub type HeightMapMaterial = ExtendedMaterial<StandardMaterial, HeightMapDisplacement>;
+
+/// Material extension that displaces a flat grid along +Z in the vertex shader.
+///
+/// Use with a flat mesh from [`crate::MeshBuilder::grid`], so that editing `height_texture`
+/// (or writing to it from a compute shader) changes the terrain without rebuilding the `Mesh`.
+/// Heights are read from the red channel, matching [`crate::ImageBufferHeightMap`].
+/// PNG heightmaps should be loaded with `is_srgb: false` so values are not gamma corrected.
+///
+/// Bevy computes the bounds used for frustum culling from the flat mesh, so they do not cover
+/// the displaced heights. Add `NoFrustumCulling`, or an `Aabb` that spans `height_scale`,
+/// or the terrain disappears when its base plane leaves the view.
+/// ```no_run
+/// use bevy::{camera::visibility::NoFrustumCulling, prelude::*};
+/// use bevy_heightmap::*;
+/// fn setup(
+///     mut meshes: ResMut<Assets<Mesh>>,
+///     mut materials: ResMut<Assets<HeightMapMaterial>>,
+///     asset_server: Res<AssetServer>,
+///     mut commands: Commands,
+/// ) {
+///     commands.spawn((
+///         Mesh3d(meshes.add(bevy_heightmap::MeshBuilder::grid(UVec2::splat(256)).build())),
+///         MeshMaterial3d(materials.add(HeightMapMaterial {
+///             base: StandardMaterial::default(),
+///             extension: HeightMapDisplacement::new(asset_server.load("textures/height.png")),
+///         })),
+///         NoFrustumCulling,
+///     ));
+/// }
+/// ```
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct HeightMapDisplacement {
+    /// Multiplier applied to the sampled height.
+    #[uniform(100)]
+    pub height_scale: f32,
+    /// Texture to sample heights from.
+    #[texture(101, sample_type = "float", filterable = false)]
+    pub height_texture: Handle<Image>,
+}
+impl HeightMapDisplacement {
+    pub const SHADER_PATH: &'static str = "embedded://bevy_heightmap/shaders/displacement.wgsl";
+
+    pub fn new(height_texture: Handle<Image>) -> Self {
+        Self {
+            height_scale: 1.,
+            height_texture,
+        }
+    }
+}
+
+impl MaterialExtension for HeightMapDisplacement {
+    fn vertex_shader() -> ShaderRef {
+        Self::SHADER_PATH.into()
+    }
+    fn prepass_vertex_shader() -> ShaderRef {
+        Self::SHADER_PATH.into()
+    }
+    fn deferred_vertex_shader() -> ShaderRef {
+        Self::SHADER_PATH.into()
+    }
+}
@@ -0,0 +1,598 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    math::{Rect, UVec2, Vec2},
+    mesh::Mesh,
+    reflect::TypePath,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    HeightGrid, Orientation,
+    asset_loader::{HeightMapLoader, HeightRemap},
+};
+
+/// Length of a degree of latitude in meters, on a sphere of the earth's mean radius.
+const METERS_PER_DEGREE: f32 = 111_195.;
+
+/// Elevations read from a GIS raster, in meters.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::gis::*;
+/// let raster = ElevationRaster::from_esri_ascii(
+///     "ncols 3\nnrows 2\nxllcorner 0\nyllcorner 0\ncellsize 30\nNODATA_value -9999\n\
+///      1 2 3\n4 -9999 6\n",
+/// )
+/// .unwrap();
+/// assert_eq!(raster.grid.size, UVec2::new(3, 2));
+/// // Rows are stored from the bottom up, with missing data at the lowest elevation.
+/// assert_eq!(raster.grid.heights[..3], [4., 1., 6.]);
+/// assert!(raster.no_data[1]);
+/// assert_eq!(raster.rect().size(), Vec2::new(60., 30.));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ElevationRaster {
+    /// Elevations with no-data points set to the lowest valid elevation.
+    pub grid: HeightGrid,
+    /// Points without data, indexed like the grid's heights.
+    pub no_data: Vec<bool>,
+    /// Distance between grid points along x and y, in meters.
+    pub cell_size: Vec2,
+}
+
+impl ElevationRaster {
+    /// Raster from elevations in rows from the top (north) down, with `no_data` marking missing values.
+    pub fn from_rows(
+        size: UVec2,
+        rows: impl IntoIterator<Item = f32>,
+        no_data: Option<f32>,
+        cell_size: Vec2,
+    ) -> Self {
+        let rows: Vec<f32> = rows.into_iter().collect();
+        let mut heights = Vec::with_capacity(rows.len());
+        for row in rows.chunks_exact(size.x as usize).rev() {
+            heights.extend_from_slice(row);
+        }
+        let no_data: Vec<bool> = heights
+            .iter()
+            .map(|&h| h.is_nan() || Some(h) == no_data)
+            .collect();
+        let lowest = heights
+            .iter()
+            .zip(&no_data)
+            .filter(|(_, no_data)| !**no_data)
+            .map(|(h, _)| *h)
+            .fold(f32::INFINITY, f32::min);
+        let lowest = if lowest.is_finite() { lowest } else { 0. };
+        for (h, no_data) in heights.iter_mut().zip(&no_data) {
+            if *no_data {
+                *h = lowest;
+            }
+        }
+        Self {
+            grid: HeightGrid::new(size, heights),
+            no_data,
+            cell_size,
+        }
+    }
+
+    /// Parses an ESRI ASCII grid: a header of `ncols`, `nrows`, `xllcorner` or `xllcenter`,
+    /// `yllcorner` or `yllcenter`, `cellsize` (or `dx` and `dy`) and the optional `NODATA_value`,
+    /// followed by the rows from the top down.
+    pub fn from_esri_ascii(text: &str) -> Result<Self, ElevationLoaderError> {
+        let mut tokens = text.split_ascii_whitespace().peekable();
+        let mut size = UVec2::ZERO;
+        let mut cell_size = Vec2::ZERO;
+        let mut no_data = None;
+        while let Some(key) = tokens.next_if(|token| token.starts_with(|c: char| c.is_alphabetic()))
+        {
+            let value = tokens
+                .next()
+                .ok_or_else(|| ElevationLoaderError::Parse(format!("missing value for {key}")))?;
+            let parse = |value: &str| {
+                value
+                    .parse::<f32>()
+                    .map_err(|_| ElevationLoaderError::Parse(format!("invalid {key}: {value}")))
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "ncols" => size.x = parse(value)? as u32,
+                "nrows" => size.y = parse(value)? as u32,
+                "cellsize" => cell_size = Vec2::splat(parse(value)?),
+                "dx" => cell_size.x = parse(value)?,
+                "dy" => cell_size.y = parse(value)?,
+                "nodata_value" => no_data = Some(parse(value)?),
+                // The position of the grid is not needed to build its mesh.
+                "xllcorner" | "xllcenter" | "yllcorner" | "yllcenter" => {
+                    parse(value)?;
+                }
+                _ => return Err(ElevationLoaderError::Parse(format!("unknown key {key}"))),
+            }
+        }
+        if size.cmplt(UVec2::splat(2)).any() || cell_size.cmple(Vec2::ZERO).any() {
+            return Err(ElevationLoaderError::Parse(format!(
+                "invalid grid of {size} cells of {cell_size}"
+            )));
+        }
+        let heights = tokens
+            .map(|token| {
+                token
+                    .parse::<f32>()
+                    .map_err(|_| ElevationLoaderError::Parse(format!("invalid height: {token}")))
+            })
+            .collect::<Result<Vec<f32>, _>>()?;
+        if heights.len() != (size.x * size.y) as usize {
+            return Err(ElevationLoaderError::Parse(format!(
+                "expected {} heights, found {}",
+                size.x * size.y,
+                heights.len()
+            )));
+        }
+        Ok(Self::from_rows(size, heights, no_data, cell_size))
+    }
+
+    /// Parses an SRTM `.hgt` tile: a square of big-endian 16-bit elevations from the top down,
+    /// with `-32768` for missing data. The tile covers one degree, and the spacing of
+    /// its columns in meters depends on its latitude, read from a `name` such as `N45E006`.
+    /// Tiles are assumed to be on the equator if `name` is `None` or not in this form.
+    pub fn from_hgt(bytes: &[u8], name: Option<&str>) -> Result<Self, ElevationLoaderError> {
+        let side = ((bytes.len() / 2) as f64).sqrt() as u32;
+        if side < 2 || (side * side * 2) as usize != bytes.len() {
+            return Err(ElevationLoaderError::Parse(format!(
+                "{} bytes is not a square tile of 16-bit elevations",
+                bytes.len()
+            )));
+        }
+        let heights = bytes
+            .chunks_exact(2)
+            .map(|bytes| i16::from_be_bytes([bytes[0], bytes[1]]) as f32);
+        let latitude = name.and_then(hgt_latitude).unwrap_or(0.) + 0.5;
+        let cell_degrees = 1. / (side - 1) as f32;
+        let cell_size =
+            Vec2::new(latitude.to_radians().cos(), 1.) * METERS_PER_DEGREE * cell_degrees;
+        Ok(Self::from_rows(
+            UVec2::splat(side),
+            heights,
+            Some(i16::MIN as f32),
+            cell_size,
+        ))
+    }
+
+    /// Makes the points without data holes, left out of meshes built from the grid.
+    pub fn with_holes(mut self) -> Self {
+        for (h, no_data) in self.grid.heights.iter_mut().zip(&self.no_data) {
+            if *no_data {
+                *h = f32::NAN;
+            }
+        }
+        self
+    }
+
+    /// Area covered by the raster in meters, centered on the origin.
+    pub fn rect(&self) -> Rect {
+        let extents = (self.grid.size - UVec2::ONE).as_vec2() * self.cell_size;
+        Rect::from_center_size(Vec2::ZERO, extents)
+    }
+}
+
+#[cfg(feature = "tiff")]
+impl ElevationRaster {
+    /// Decodes a single channel TIFF of integer or floating point elevations, such as a DEM.
+    /// Missing data is read from the GDAL no-data tag.
+    ///
+    /// With a GeoTIFF pixel scale, cells are sized in the units of the model, and for geographic
+    /// models in degrees, converted to meters at the latitude of the raster's center.
+    /// Without one, cells are one unit wide.
+    pub fn from_tiff(bytes: &[u8]) -> Result<Self, ElevationLoaderError> {
+        use tiff::{
+            ColorType,
+            decoder::{Decoder, DecodingResult},
+            tags::Tag,
+        };
+        let mut decoder = Decoder::new(std::io::Cursor::new(bytes))?;
+        let (width, height) = decoder.dimensions()?;
+        let color_type = decoder.colortype()?;
+        if !matches!(color_type, ColorType::Gray(_)) {
+            return Err(ElevationLoaderError::Parse(format!(
+                "expected a single channel of elevations, found {color_type:?}"
+            )));
+        }
+        let no_data = decoder
+            .find_tag(Tag::GdalNodata)?
+            .and_then(|value| value.into_string().ok())
+            .and_then(|value| value.trim_matches(char::from(0)).trim().parse::<f32>().ok());
+        let cell_size = match decoder.find_tag(Tag::ModelPixelScaleTag)? {
+            Some(scale) => {
+                let &[x, y, ..] = scale.into_f64_vec()?.as_slice() else {
+                    return Err(ElevationLoaderError::Parse(
+                        "pixel scale needs x and y values".to_string(),
+                    ));
+                };
+                let mut cell_size = Vec2::new(x as f32, y as f32);
+                // Models are projected unless the geo keys say otherwise.
+                let geographic = decoder
+                    .find_tag(Tag::GeoKeyDirectoryTag)?
+                    .map(|keys| keys.into_u16_vec())
+                    .transpose()?
+                    .is_some_and(|keys| {
+                        keys.chunks_exact(4).skip(1).any(|key| {
+                            key[0] == GT_MODEL_TYPE_GEO_KEY && key[3] == GEOGRAPHIC_MODEL
+                        })
+                    });
+                if geographic {
+                    let top = match decoder.find_tag(Tag::ModelTiepointTag)? {
+                        Some(tiepoint) => match tiepoint.into_f64_vec()?.as_slice() {
+                            // Raster point (i, j, k) followed by model point (x, y, z).
+                            &[_, _, _, _, y, ..] => y as f32,
+                            _ => {
+                                return Err(ElevationLoaderError::Parse(
+                                    "tiepoint needs raster and model coordinates".to_string(),
+                                ));
+                            }
+                        },
+                        None => 0.,
+                    };
+                    let latitude = top - 0.5 * height as f32 * cell_size.y;
+                    cell_size *= Vec2::new(latitude.to_radians().cos(), 1.) * METERS_PER_DEGREE;
+                }
+                cell_size
+            }
+            None => Vec2::ONE,
+        };
+        let heights: Vec<f32> = match decoder.read_image()? {
+            DecodingResult::U8(data) => data.into_iter().map(f32::from).collect(),
+            DecodingResult::U16(data) => data.into_iter().map(f32::from).collect(),
+            DecodingResult::U32(data) => data.into_iter().map(|h| h as f32).collect(),
+            DecodingResult::U64(data) => data.into_iter().map(|h| h as f32).collect(),
+            DecodingResult::F16(data) => data.into_iter().map(|h| h.to_f32()).collect(),
+            DecodingResult::F32(data) => data,
+            DecodingResult::F64(data) => data.into_iter().map(|h| h as f32).collect(),
+            DecodingResult::I8(data) => data.into_iter().map(f32::from).collect(),
+            DecodingResult::I16(data) => data.into_iter().map(f32::from).collect(),
+            DecodingResult::I32(data) => data.into_iter().map(|h| h as f32).collect(),
+            DecodingResult::I64(data) => data.into_iter().map(|h| h as f32).collect(),
+        };
+        Ok(Self::from_rows(
+            UVec2::new(width, height),
+            heights,
+            no_data,
+            cell_size,
+        ))
+    }
+}
+
+/// GeoTIFF key for the type of model, and its value for latitude and longitude models.
+#[cfg(feature = "tiff")]
+const GT_MODEL_TYPE_GEO_KEY: u16 = 1024;
+#[cfg(feature = "tiff")]
+const GEOGRAPHIC_MODEL: u16 = 2;
+
+/// Latitude of the southern edge of an SRTM tile named like `N45E006` or `S12W077`.
+fn hgt_latitude(name: &str) -> Option<f32> {
+    let sign = match name.get(..1)? {
+        "N" | "n" => 1.,
+        "S" | "s" => -1.,
+        _ => return None,
+    };
+    Some(sign * name.get(1..3)?.parse::<f32>().ok()?)
+}
+
+/// Settings for the [`EsriAsciiLoader`], the [`SrtmLoader`] and the `TiffLoader`.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ElevationLoaderSettings {
+    /// Up axis of the loaded mesh.
+    pub orientation: Orientation,
+    /// Whether points without data are holes in the mesh, and NaN in the `HeightGrid`,
+    /// instead of set to the lowest elevation.
+    pub holes: bool,
+    /// Remapping of the elevations in meters, applied before the mesh is built.
+    pub remap: HeightRemap,
+}
+
+/// Builds the mesh of a raster and adds its grid as a labeled asset, like [`HeightMapLoader`].
+fn load_raster(
+    mut raster: ElevationRaster,
+    settings: &ElevationLoaderSettings,
+    load_context: &mut LoadContext<'_>,
+) -> Mesh {
+    if settings.holes {
+        raster = raster.with_holes();
+    }
+    settings.remap.apply(&mut raster.grid);
+    let mesh = raster
+        .grid
+        .mesh_builder(raster.rect())
+        .with_orientation(settings.orientation)
+        .build();
+    load_context.add_labeled_asset(HeightMapLoader::HEIGHT_GRID_LABEL.to_string(), raster.grid);
+    mesh
+}
+
+/// Loader for ESRI ASCII grids (`.asc`), with heights and horizontal extents in meters.
+/// The elevations are also available as a [`HeightGrid`] under the `HeightGrid` label.
+#[derive(Clone, Default, TypePath)]
+pub struct EsriAsciiLoader;
+impl AssetLoader for EsriAsciiLoader {
+    type Asset = Mesh;
+    type Settings = ElevationLoaderSettings;
+    type Error = ElevationLoaderError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &ElevationLoaderSettings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Mesh, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let text = std::str::from_utf8(&bytes)
+            .map_err(|err| ElevationLoaderError::Parse(err.to_string()))?;
+        let raster = ElevationRaster::from_esri_ascii(text)?;
+        Ok(load_raster(raster, settings, load_context))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["asc"]
+    }
+}
+
+/// Loader for SRTM tiles (`.hgt`), with heights and horizontal extents in meters.
+/// The elevations are also available as a [`HeightGrid`] under the `HeightGrid` label.
+#[derive(Clone, Default, TypePath)]
+pub struct SrtmLoader;
+impl AssetLoader for SrtmLoader {
+    type Asset = Mesh;
+    type Settings = ElevationLoaderSettings;
+    type Error = ElevationLoaderError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &ElevationLoaderSettings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Mesh, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let name = load_context
+            .path()
+            .path()
+            .file_stem()
+            .and_then(|name| name.to_str());
+        let raster = ElevationRaster::from_hgt(&bytes, name)?;
+        Ok(load_raster(raster, settings, load_context))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["hgt"]
+    }
+}
+
+/// Loader for single channel TIFF and GeoTIFF elevation rasters (`.tif`, `.tiff`),
+/// sized by their pixel scale. Requires the `tiff` feature.
+/// The elevations are also available as a [`HeightGrid`] under the `HeightGrid` label.
+#[cfg(feature = "tiff")]
+#[derive(Clone, Default, TypePath)]
+pub struct TiffLoader;
+#[cfg(feature = "tiff")]
+impl AssetLoader for TiffLoader {
+    type Asset = Mesh;
+    type Settings = ElevationLoaderSettings;
+    type Error = ElevationLoaderError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &ElevationLoaderSettings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Mesh, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let raster = ElevationRaster::from_tiff(&bytes)?;
+        Ok(load_raster(raster, settings, load_context))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tif", "tiff"]
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ElevationLoaderError {
+    #[error("Could not read elevation file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse elevation file: {0}")]
+    Parse(String),
+    #[cfg(feature = "tiff")]
+    #[error("Could not decode TIFF: {0}")]
+    Tiff(#[from] tiff::TiffError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Big-endian bytes of elevations.
+    fn hgt(heights: &[i16]) -> Vec<u8> {
+        heights.iter().flat_map(|h| h.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn hgt_rows_are_big_endian_from_the_north() {
+        let raster = ElevationRaster::from_hgt(&hgt(&[1, 256, -2, i16::MIN]), None).unwrap();
+        assert_eq!(raster.grid.size, UVec2::splat(2));
+        // The southern row comes first, with missing data at the lowest elevation.
+        assert_eq!(raster.grid.heights, [-2., -2., 1., 256.]);
+        assert_eq!(raster.no_data, [false, true, false, false]);
+        let raster = raster.with_holes();
+        assert!(raster.grid.get(UVec2::new(1, 0)).is_nan());
+        assert_eq!(raster.grid.get(UVec2::new(0, 0)), -2.);
+    }
+
+    #[test]
+    fn hgt_must_be_square() {
+        for bytes in [hgt(&[1, 2, 3]), hgt(&[1; 6]), vec![0; 9], hgt(&[1])] {
+            assert!(matches!(
+                ElevationRaster::from_hgt(&bytes, None),
+                Err(ElevationLoaderError::Parse(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn hgt_cell_size_depends_on_latitude() {
+        assert_eq!(hgt_latitude("N45E006"), Some(45.));
+        assert_eq!(hgt_latitude("S12W077"), Some(-12.));
+        assert_eq!(hgt_latitude("tile"), None);
+        let bytes = hgt(&[0; 9]);
+        let cell_size = |name| ElevationRaster::from_hgt(&bytes, name).unwrap().cell_size;
+        // Half a degree between points, with columns narrowing at the tile's central latitude.
+        let half_degree = 0.5 * METERS_PER_DEGREE;
+        for (name, latitude) in [
+            (Some("N45E006"), 45.5f32),
+            (Some("S12W077"), -11.5),
+            (None, 0.5),
+        ] {
+            let expected = Vec2::new(latitude.to_radians().cos() * half_degree, half_degree);
+            assert!(cell_size(name).abs_diff_eq(expected, 1e-2), "{name:?}");
+        }
+        assert!(cell_size(Some("N45E006")).x < cell_size(Some("S12W077")).x);
+    }
+
+    /// GeoTIFF tags of a test image.
+    #[cfg(feature = "tiff")]
+    #[derive(Default)]
+    struct GeoTags<'a> {
+        pixel_scale: Option<&'a [f64]>,
+        tiepoint: Option<&'a [f64]>,
+        geographic: bool,
+        no_data: Option<&'a str>,
+    }
+
+    /// Encodes a single channel TIFF of `size` with rows from the top down.
+    #[cfg(feature = "tiff")]
+    fn tiff<C: tiff::encoder::colortype::ColorType>(
+        size: UVec2,
+        data: &[C::Inner],
+        tags: GeoTags,
+    ) -> Vec<u8>
+    where
+        [C::Inner]: tiff::encoder::TiffValue,
+    {
+        use tiff::{encoder::TiffEncoder, tags::Tag};
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut bytes).unwrap();
+        let mut image = encoder.new_image::<C>(size.x, size.y).unwrap();
+        let directory = image.encoder();
+        if let Some(pixel_scale) = tags.pixel_scale {
+            directory
+                .write_tag(Tag::ModelPixelScaleTag, pixel_scale)
+                .unwrap();
+        }
+        if let Some(tiepoint) = tags.tiepoint {
+            directory
+                .write_tag(Tag::ModelTiepointTag, tiepoint)
+                .unwrap();
+        }
+        if tags.geographic {
+            // Header, then the model type key.
+            let keys: [u16; 8] = [1, 1, 0, 1, GT_MODEL_TYPE_GEO_KEY, 0, 1, GEOGRAPHIC_MODEL];
+            directory
+                .write_tag(Tag::GeoKeyDirectoryTag, &keys[..])
+                .unwrap();
+        }
+        if let Some(no_data) = tags.no_data {
+            directory.write_tag(Tag::GdalNodata, no_data).unwrap();
+        }
+        image.write_data(data).unwrap();
+        bytes.into_inner()
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn tiff_rows_are_flipped_and_scaled() {
+        use tiff::encoder::colortype::Gray32Float;
+        let bytes = tiff::<Gray32Float>(
+            UVec2::new(3, 2),
+            &[1., 2., 3., 4., 5., 6.],
+            GeoTags {
+                pixel_scale: Some(&[30., 20., 0.]),
+                ..GeoTags::default()
+            },
+        );
+        let raster = ElevationRaster::from_tiff(&bytes).unwrap();
+        assert_eq!(raster.grid.size, UVec2::new(3, 2));
+        assert_eq!(raster.grid.heights, [4., 5., 6., 1., 2., 3.]);
+        assert!(raster.no_data.iter().all(|no_data| !no_data));
+        // Projected models are in meters.
+        assert_eq!(raster.cell_size, Vec2::new(30., 20.));
+
+        let bytes = tiff::<Gray32Float>(UVec2::splat(2), &[0.; 4], GeoTags::default());
+        let raster = ElevationRaster::from_tiff(&bytes).unwrap();
+        assert_eq!(raster.cell_size, Vec2::ONE);
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn tiff_no_data_becomes_holes() {
+        use tiff::encoder::colortype::GrayI16;
+        let bytes = tiff::<GrayI16>(
+            UVec2::splat(2),
+            &[-9999, 10, -20, 30],
+            GeoTags {
+                no_data: Some("-9999"),
+                ..GeoTags::default()
+            },
+        );
+        let raster = ElevationRaster::from_tiff(&bytes).unwrap();
+        assert_eq!(raster.grid.heights, [-20., 30., -20., 10.]);
+        assert_eq!(raster.no_data, [false, false, true, false]);
+        let raster = raster.with_holes();
+        assert!(raster.grid.get(UVec2::new(0, 1)).is_nan());
+        assert_eq!(raster.grid.get(UVec2::new(1, 1)), 10.);
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn tiff_degrees_are_converted_to_meters() {
+        use tiff::encoder::colortype::Gray32Float;
+        let bytes = tiff::<Gray32Float>(
+            UVec2::splat(3),
+            &[0.; 9],
+            GeoTags {
+                pixel_scale: Some(&[0.5, 0.5, 0.]),
+                // The top left corner at 6E 46N.
+                tiepoint: Some(&[0., 0., 0., 6., 46., 0.]),
+                geographic: true,
+                ..GeoTags::default()
+            },
+        );
+        let raster = ElevationRaster::from_tiff(&bytes).unwrap();
+        // Three rows of half a degree, centered on 45.25N.
+        let half_degree = 0.5 * METERS_PER_DEGREE;
+        let expected = Vec2::new(45.25f32.to_radians().cos() * half_degree, half_degree);
+        assert!(raster.cell_size.abs_diff_eq(expected, 1e-2));
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn tiff_short_geo_tags_are_rejected() {
+        use tiff::encoder::colortype::Gray32Float;
+        let parse_error = |tags| {
+            let bytes = tiff::<Gray32Float>(UVec2::splat(2), &[0.; 4], tags);
+            match ElevationRaster::from_tiff(&bytes) {
+                Err(ElevationLoaderError::Parse(error)) => error,
+                result => panic!("expected a parse error, found {result:?}"),
+            }
+        };
+        let error = parse_error(GeoTags {
+            pixel_scale: Some(&[30.]),
+            ..GeoTags::default()
+        });
+        assert!(error.contains("pixel scale"), "{error}");
+        let error = parse_error(GeoTags {
+            pixel_scale: Some(&[0.5, 0.5, 0.]),
+            tiepoint: Some(&[0., 0., 0., 6.]),
+            geographic: true,
+            ..GeoTags::default()
+        });
+        assert!(error.contains("tiepoint"), "{error}");
+    }
+}
@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+
+use bevy::{
+    asset::Asset,
+    math::{FloatExt, IVec2, Rect, URect, UVec2, Vec2},
+    mesh::Mesh,
+    reflect::TypePath,
+};
+
+use crate::{HeightMap, MeshBuilder, Orientation};
+
+/// Offsets of the eight neighbors of a grid point, starting east and going counterclockwise.
+pub const D8_OFFSETS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+];
+
+/// Editable grid of height samples, one per vertex of [`MeshBuilder::grid`].
+///
+/// Heights are stored row by row starting at the bottom (`-y`) edge, so the height at
+/// grid point `(x, y)` belongs to vertex `MeshBuilder::index(x, y, size.x)`.
+/// NaN heights mark holes, where there is no terrain. Meshes built from the grid leave out the
+/// triangles touching them, and the rest of the crate treats them alike: sculpting leaves them
+/// untouched, statistics and contours skip them, heightfields remove the cells around them,
+/// paths avoid them, lines of sight pass over them and water drains into them like off the edge.
+/// The [`crate::asset_loader::HeightMapLoader`] provides the loaded grid under the `HeightGrid` label:
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::*;
+/// fn setup(asset_server: Res<AssetServer>) {
+///     let grid: Handle<HeightGrid> = asset_server.load("textures/terrain.hmp.png#HeightGrid");
+/// }
+/// ```
+#[derive(Asset, TypePath, Clone, Debug, Default, PartialEq)]
+pub struct HeightGrid {
+    pub size: UVec2,
+    pub heights: Vec<f32>,
+}
+
+impl HeightGrid {
+    pub fn new(size: UVec2, heights: Vec<f32>) -> Self {
+        assert_eq!(heights.len(), size.x as usize * size.y as usize);
+        Self { size, heights }
+    }
+
+    /// Grid with every height set to `h`.
+    pub fn flat(size: UVec2, h: f32) -> Self {
+        Self::new(size, vec![h; size.x as usize * size.y as usize])
+    }
+
+    /// Samples `heightmap` at the vertices of a grid mesh of `size`.
+    pub fn from_heightmap<H: HeightMap + ?Sized>(heightmap: &H, size: UVec2) -> Self {
+        let mut builder = MeshBuilder::grid(size);
+        builder.update_z_positions(heightmap);
+        Self::new(size, builder.positions.iter().map(|p| p[2]).collect())
+    }
+
+    pub fn index(&self, xy: UVec2) -> usize {
+        MeshBuilder::index(xy.x, xy.y, self.size.x) as usize
+    }
+
+    pub fn get(&self, xy: UVec2) -> f32 {
+        self.heights[self.index(xy)]
+    }
+
+    pub fn get_mut(&mut self, xy: UVec2) -> &mut f32 {
+        let index = self.index(xy);
+        &mut self.heights[index]
+    }
+
+    /// Grid points within the grid around `xy`, in the order of [`D8_OFFSETS`].
+    pub fn neighbors(&self, xy: UVec2) -> impl Iterator<Item = UVec2> {
+        let size = self.size.as_ivec2();
+        D8_OFFSETS.into_iter().filter_map(move |offset| {
+            let p = xy.as_ivec2() + offset;
+            (p.cmpge(IVec2::ZERO).all() && p.cmplt(size).all()).then(|| p.as_uvec2())
+        })
+    }
+
+    /// Rectangle covering every grid point, as used by `region` arguments.
+    pub fn rect(&self) -> URect {
+        URect::from_corners(UVec2::ZERO, self.size)
+    }
+
+    /// Heightmap space position of a grid point.
+    pub fn position(&self, xy: UVec2) -> Vec2 {
+        xy.as_vec2() / (self.size - UVec2::ONE).as_vec2() - Vec2::splat(0.5)
+    }
+
+    /// Continuous grid coordinates of a heightmap space position.
+    pub fn grid_position(&self, p: Vec2) -> Vec2 {
+        (p + Vec2::splat(0.5)) * (self.size - UVec2::ONE).as_vec2()
+    }
+
+    /// Position of a grid point in the XY plane of a mesh built over `rect`.
+    pub fn rect_position(&self, xy: UVec2, rect: Rect) -> Vec2 {
+        rect.center() + self.position(xy) * rect.size()
+    }
+
+    /// Continuous grid coordinates of a position in the XY plane of a mesh built over `rect`.
+    pub fn rect_grid_position(&self, p: Vec2, rect: Rect) -> Vec2 {
+        self.grid_position((p - rect.center()) / rect.size())
+    }
+
+    /// Region of grid points (`min` inclusive, `max` exclusive) within `radius` of `p`.
+    pub fn region_around(&self, p: Vec2, radius: f32) -> URect {
+        MeshBuilder::grid_region(
+            self.size,
+            Rect::from_center_half_size(p, Vec2::splat(radius)),
+        )
+    }
+
+    /// Builds a grid mesh with one vertex per height sample.
+    pub fn build_mesh(&self) -> Mesh {
+        self.mesh_builder(MeshBuilder::UNIT_RECT).build()
+    }
+
+    /// Grid mesh builder spanning `rect`, with one vertex per height sample.
+    pub fn mesh_builder(&self, rect: Rect) -> MeshBuilder {
+        let mut builder = MeshBuilder::grid_rect(self.size, rect);
+        for (p, h) in builder.positions.iter_mut().zip(&self.heights) {
+            p[2] = *h;
+        }
+        builder
+    }
+
+    /// Updates the positions and normals of a mesh from [`HeightGrid::build_mesh`]
+    /// or [`HeightGrid::mesh_builder`] for the grid points in `region` (`min` inclusive, `max` exclusive).
+    /// `orientation` must match the one the mesh was built with.
+    /// Returns `false`, leaving the mesh untouched, if it was not built from this grid
+    /// or is not indexed, as with [`crate::Shading::Flat`].
+    pub fn update_mesh(&self, mesh: &mut Mesh, orientation: Orientation, region: URect) -> bool {
+        MeshBuilder::update_grid_region(mesh, self.size, orientation, region, |xy, _| self.get(xy))
+    }
+}
+
+impl HeightMap for HeightGrid {
+    /// Bilinearly interpolates between the nearest grid points, and `None` next to a hole.
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        let h = self.h(p);
+        (!h.is_nan()).then_some(h)
+    }
+
+    /// Bilinearly interpolates between the nearest grid points.
+    /// Grid points get their exact sample, and holes only affect the cells around them.
+    fn h(&self, p: Vec2) -> f32 {
+        let bounds = self.size - UVec2::ONE;
+        let xy = self.grid_position(p).clamp(Vec2::ZERO, bounds.as_vec2());
+        // Snap positions that are grid points up to rounding.
+        let rounded = xy.round();
+        let xy = Vec2::select((xy - rounded).abs().cmplt(Vec2::splat(1e-4)), rounded, xy);
+        let min = xy.floor().as_uvec2().min(bounds);
+        let max = (min + UVec2::ONE).min(bounds);
+        let t = xy - min.as_vec2();
+        // Corners without weight are skipped, so a hole there does not make the result NaN.
+        let lerp = |a: f32, b: f32, t: f32| if t == 0. { a } else { a.lerp(b, t) };
+        let bottom = lerp(self.get(min), self.get(UVec2::new(max.x, min.y)), t.x);
+        let top = lerp(self.get(UVec2::new(min.x, max.y)), self.get(max), t.x);
+        lerp(bottom, top, t.y)
+    }
+}
+
+/// Entry of a priority queue, ordered so the lowest key is popped first.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Lowest<T>(pub f32, pub T);
+impl<T> PartialEq for Lowest<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl<T> Eq for Lowest<T> {}
+impl<T> PartialOrd for Lowest<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T> Ord for Lowest<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holed() -> HeightGrid {
+        let size = UVec2::new(7, 5);
+        let mut grid = HeightGrid::new(
+            size,
+            (0..size.element_product())
+                .map(|i| ((i * 37) % 11) as f32 / 20.)
+                .collect(),
+        );
+        for xy in [UVec2::new(3, 2), UVec2::new(6, 4), UVec2::new(0, 1)] {
+            *grid.get_mut(xy) = f32::NAN;
+        }
+        grid
+    }
+
+    #[test]
+    fn resampling_at_the_same_size_keeps_holes() {
+        let grid = holed();
+        let resampled = HeightGrid::from_heightmap(&grid, grid.size);
+        for (i, (h, resampled)) in grid.heights.iter().zip(&resampled.heights).enumerate() {
+            assert!(
+                h == resampled || h.is_nan() && resampled.is_nan(),
+                "{i}: {h} != {resampled}"
+            );
+        }
+    }
+
+    #[test]
+    fn holes_only_affect_their_cells() {
+        let grid = holed();
+        // On the edge between (3, 1) and (4, 1), below the hole at (3, 2).
+        let edge = (grid.position(UVec2::new(3, 1)) + grid.position(UVec2::new(4, 1))) / 2.;
+        let expected = (grid.get(UVec2::new(3, 1)) + grid.get(UVec2::new(4, 1))) / 2.;
+        assert!((grid.try_h(edge).unwrap() - expected).abs() < 1e-6);
+        let next_to_hole = (grid.position(UVec2::new(2, 2)) + grid.position(UVec2::new(3, 2))) / 2.;
+        assert_eq!(grid.try_h(next_to_hole), None);
+    }
+}
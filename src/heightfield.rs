@@ -0,0 +1,159 @@
+use bevy::math::{Rect, UVec2, Vec3};
+
+use crate::{HeightGrid, HeightMap};
+
+/// Heights of a grid in the layout of physics engine heightfield colliders, such as `parry3d`'s:
+/// Y-up and centered on its origin, with rows along Z and columns along X, scaled by `scale`.
+///
+/// Matches a mesh built from the same grid and rectangle with [`crate::Orientation::YUp`]
+/// and [`crate::Triangulation::Uniform`], once the collider is placed at `translation`.
+/// For [`crate::Orientation::ZUp`] meshes, also rotate the collider by `Quat::from_rotation_x(FRAC_PI_2)`.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{heightfield::*, *};
+/// let grid = HeightGrid::from_heightmap(&ValueFunctionHeightMap(|p: Vec2| p.x), UVec2::new(8, 4));
+/// let heightfield = Heightfield::from_grid(&grid, Rect::new(0., 0., 70., 30.), 10.);
+/// assert_eq!((heightfield.rows, heightfield.columns), (4, 8));
+/// assert_eq!(heightfield.scale, Vec3::new(70., 10., 30.));
+/// assert_eq!(heightfield.translation, Vec3::new(35., 0., -15.));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Heightfield {
+    pub rows: usize,
+    pub columns: usize,
+    /// Heights in row-major order, with row 0 at `-Z`, the top of a heightmap image.
+    /// Holes are at zero height.
+    pub heights: Vec<f32>,
+    /// Cells, by the row and column of their first corner, with a hole at any corner.
+    /// Physics engines can remove them, as the `parry3d` conversion does.
+    pub removed_cells: Vec<(usize, usize)>,
+    /// Size along X and Z, and the multiplier for heights.
+    pub scale: Vec3,
+    /// Position of the heightfield's center relative to the mesh's origin.
+    pub translation: Vec3,
+}
+
+impl Heightfield {
+    /// Heightfield of a grid spanning `rect` in the mesh's XY plane, with heights multiplied by `height_scale`.
+    pub fn from_grid(grid: &HeightGrid, rect: Rect, height_scale: f32) -> Self {
+        let (rows, columns) = (grid.size.y as usize, grid.size.x as usize);
+        let mut heights = Vec::with_capacity(rows * columns);
+        for y in (0..grid.size.y).rev() {
+            for x in 0..grid.size.x {
+                heights.push(grid.get(UVec2::new(x, y)));
+            }
+        }
+        let is_hole = |row: usize, column: usize| heights[row * columns + column].is_nan();
+        let mut removed_cells = Vec::new();
+        for row in 0..rows.saturating_sub(1) {
+            for column in 0..columns.saturating_sub(1) {
+                if [(0, 0), (0, 1), (1, 0), (1, 1)]
+                    .iter()
+                    .any(|(dr, dc)| is_hole(row + dr, column + dc))
+                {
+                    removed_cells.push((row, column));
+                }
+            }
+        }
+        for h in heights.iter_mut().filter(|h| h.is_nan()) {
+            *h = 0.;
+        }
+        let center = rect.center();
+        Self {
+            rows,
+            columns,
+            heights,
+            removed_cells,
+            scale: Vec3::new(rect.width(), height_scale, rect.height()),
+            translation: Vec3::new(center.x, 0., -center.y),
+        }
+    }
+
+    /// Heightfield sampled from a heightmap like [`HeightGrid::from_heightmap`],
+    /// the same sampling as the meshes built by [`crate::MeshBuilder::grid`].
+    pub fn from_heightmap<H: HeightMap + ?Sized>(
+        heightmap: &H,
+        size: UVec2,
+        rect: Rect,
+        height_scale: f32,
+    ) -> Self {
+        Self::from_grid(
+            &HeightGrid::from_heightmap(heightmap, size),
+            rect,
+            height_scale,
+        )
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> f32 {
+        self.heights[row * self.columns + column]
+    }
+
+    /// Heights in column-major order, as taken by `nalgebra::DMatrix::from_vec(rows, columns, ..)`.
+    pub fn column_major(&self) -> Vec<f32> {
+        let mut heights = Vec::with_capacity(self.heights.len());
+        for column in 0..self.columns {
+            for row in 0..self.rows {
+                heights.push(self.get(row, column));
+            }
+        }
+        heights
+    }
+}
+
+#[cfg(feature = "parry3d")]
+impl From<&Heightfield> for parry3d::shape::HeightField {
+    fn from(heightfield: &Heightfield) -> Self {
+        use parry3d::na::{DMatrix, Vector3};
+        use parry3d::shape::HeightFieldCellStatus;
+        let scale = heightfield.scale;
+        let mut shape = Self::new(
+            DMatrix::from_vec(
+                heightfield.rows,
+                heightfield.columns,
+                heightfield.column_major(),
+            ),
+            Vector3::new(scale.x, scale.y, scale.z),
+        );
+        for &(row, column) in &heightfield.removed_cells {
+            shape.set_cell_status(row, column, HeightFieldCellStatus::CELL_REMOVED);
+        }
+        shape
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holes_remove_cells() {
+        // A hole at grid point (1, 2), row 0 of the heightfield.
+        let mut grid = HeightGrid::flat(UVec2::new(4, 3), 1.);
+        *grid.get_mut(UVec2::new(1, 2)) = f32::NAN;
+        let heightfield = Heightfield::from_grid(&grid, Rect::new(0., 0., 3., 2.), 1.);
+        assert!(heightfield.heights.iter().all(|h| !h.is_nan()));
+        assert_eq!(heightfield.get(0, 1), 0.);
+        assert_eq!(heightfield.removed_cells, vec![(0, 0), (0, 1)]);
+    }
+
+    #[cfg(feature = "parry3d")]
+    #[test]
+    fn parry_heightfield_leaves_out_holes() {
+        use parry3d::shape::{HeightField, HeightFieldCellStatus};
+        // A hole at grid point (0, 0), in the last row of the heightfield.
+        let mut grid = HeightGrid::flat(UVec2::new(3, 3), 1.);
+        *grid.get_mut(UVec2::new(0, 0)) = f32::NAN;
+        let shape = HeightField::from(&Heightfield::from_grid(
+            &grid,
+            Rect::new(0., 0., 2., 2.),
+            1.,
+        ));
+        assert_eq!(shape.cell_status(1, 0), HeightFieldCellStatus::CELL_REMOVED);
+        assert_eq!(shape.triangles().count(), 6);
+        assert!(shape.triangles().all(|triangle| {
+            [triangle.a, triangle.b, triangle.c]
+                .iter()
+                .all(|p| p.y == 1.)
+        }));
+    }
+}
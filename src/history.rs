@@ -0,0 +1,276 @@
+use std::collections::VecDeque;
+
+use bevy::math::{URect, UVec2};
+
+use crate::HeightGrid;
+
+/// Heights of a region of a [`HeightGrid`] from the other side of an edit.
+///
+/// Undoing or redoing swaps these heights with the grid's, so one buffer serves both directions.
+#[derive(Clone, Debug)]
+pub struct HeightGridEdit {
+    pub region: URect,
+    pub heights: Vec<f32>,
+}
+impl HeightGridEdit {
+    /// Copies the heights of `region` (`min` inclusive, `max` exclusive).
+    pub fn snapshot(grid: &HeightGrid, region: URect) -> Self {
+        let region = region.intersect(grid.rect());
+        let mut heights = Vec::with_capacity(region.size().element_product() as usize);
+        for y in region.min.y..region.max.y {
+            for x in region.min.x..region.max.x {
+                heights.push(grid.get(UVec2::new(x, y)));
+            }
+        }
+        Self { region, heights }
+    }
+
+    /// Exchanges the stored heights with the grid's.
+    fn swap(&mut self, grid: &mut HeightGrid) {
+        let mut heights = self.heights.iter_mut();
+        for y in self.region.min.y..self.region.max.y {
+            for x in self.region.min.x..self.region.max.x {
+                std::mem::swap(grid.get_mut(UVec2::new(x, y)), heights.next().unwrap());
+            }
+        }
+    }
+
+    fn memory(&self) -> usize {
+        std::mem::size_of::<Self>() + self.heights.len() * std::mem::size_of::<f32>()
+    }
+}
+
+/// Group of edits undone and redone together, such as a brush stroke.
+#[derive(Clone, Debug, Default)]
+struct HeightGridStep {
+    edits: Vec<HeightGridEdit>,
+}
+impl HeightGridStep {
+    fn memory(&self) -> usize {
+        self.edits.iter().map(HeightGridEdit::memory).sum()
+    }
+
+    fn region(&self) -> URect {
+        self.edits
+            .iter()
+            .map(|edit| edit.region)
+            .reduce(|a, b| a.union(b))
+            .unwrap_or_default()
+    }
+
+    /// Swaps edits in reverse so overlapping edits restore the right heights.
+    fn undo(&mut self, grid: &mut HeightGrid) -> URect {
+        for edit in self.edits.iter_mut().rev() {
+            edit.swap(grid);
+        }
+        self.region()
+    }
+
+    fn redo(&mut self, grid: &mut HeightGrid) -> URect {
+        for edit in self.edits.iter_mut() {
+            edit.swap(grid);
+        }
+        self.region()
+    }
+}
+
+/// Undo/redo history of edits to a [`HeightGrid`], bounded by a memory budget.
+///
+/// Returned regions can be passed to [`HeightGrid::update_mesh`].
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{history::*, sculpt::*, *};
+/// let mut grid = HeightGrid::flat(UVec2::new(16, 16), 0.);
+/// let mut history = HeightGridHistory::new(1 << 20);
+/// let brush = Brush::new(BrushKind::Raise, 0.2);
+/// let region = brush.region(&grid, Vec2::ZERO);
+/// history.edit(&mut grid, region, |grid| {
+///     brush.apply(grid, Vec2::ZERO);
+/// });
+/// history.undo(&mut grid);
+/// assert_eq!(grid, HeightGrid::flat(UVec2::new(16, 16), 0.));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeightGridHistory {
+    /// Maximum bytes of height data kept for undo and redo.
+    pub memory_budget: usize,
+    undo: VecDeque<HeightGridStep>,
+    redo: Vec<HeightGridStep>,
+    stroke: Option<HeightGridStep>,
+    memory: usize,
+}
+impl HeightGridHistory {
+    pub fn new(memory_budget: usize) -> Self {
+        Self {
+            memory_budget,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            stroke: None,
+            memory: 0,
+        }
+    }
+
+    /// Applies `edit` to the grid, recording the previous heights of `region`.
+    /// The edit must not modify heights outside of `region`.
+    pub fn edit(
+        &mut self,
+        grid: &mut HeightGrid,
+        region: URect,
+        edit: impl FnOnce(&mut HeightGrid),
+    ) {
+        let snapshot = HeightGridEdit::snapshot(grid, region);
+        edit(grid);
+        self.record(snapshot);
+    }
+
+    /// Records the heights of a region from before an edit was applied.
+    pub fn record(&mut self, edit: HeightGridEdit) {
+        self.clear_redo();
+        self.memory += edit.memory();
+        match &mut self.stroke {
+            Some(stroke) => stroke.edits.push(edit),
+            None => self.undo.push_back(HeightGridStep { edits: vec![edit] }),
+        }
+        self.enforce_budget();
+    }
+
+    /// Groups edits until [`HeightGridHistory::end_stroke`] into a single undo step.
+    pub fn begin_stroke(&mut self) {
+        self.end_stroke();
+        self.stroke = Some(HeightGridStep::default());
+    }
+
+    /// Ends the current stroke. Strokes can outgrow the budget while they are recorded,
+    /// so the oldest steps are dropped now, including the stroke itself if it alone is too large.
+    pub fn end_stroke(&mut self) {
+        if let Some(stroke) = self.stroke.take()
+            && !stroke.edits.is_empty()
+        {
+            self.undo.push_back(stroke);
+            self.enforce_budget();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || self.stroke.as_ref().is_some_and(|s| !s.edits.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Reverts the most recent step, returning the modified region.
+    pub fn undo(&mut self, grid: &mut HeightGrid) -> Option<URect> {
+        self.end_stroke();
+        let mut step = self.undo.pop_back()?;
+        let region = step.undo(grid);
+        self.redo.push(step);
+        Some(region)
+    }
+
+    /// Reapplies the most recently undone step, returning the modified region.
+    pub fn redo(&mut self, grid: &mut HeightGrid) -> Option<URect> {
+        let mut step = self.redo.pop()?;
+        let region = step.redo(grid);
+        self.undo.push_back(step);
+        Some(region)
+    }
+
+    /// Discards all history.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.stroke = None;
+        self.memory = 0;
+    }
+
+    /// Bytes of height data currently stored.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    fn clear_redo(&mut self) {
+        self.memory -= self.redo.iter().map(HeightGridStep::memory).sum::<usize>();
+        self.redo.clear();
+    }
+
+    /// Drops the oldest steps until the history fits in the budget.
+    fn enforce_budget(&mut self) {
+        while self.memory > self.memory_budget {
+            let Some(step) = self.undo.pop_front() else {
+                break;
+            };
+            self.memory -= step.memory();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raise(history: &mut HeightGridHistory, grid: &mut HeightGrid, xy: UVec2) {
+        let region = URect::from_corners(xy, xy + UVec2::ONE);
+        history.edit(grid, region, |grid| *grid.get_mut(xy) += 1.);
+    }
+
+    #[test]
+    fn redo_restores_undone_edits() {
+        let mut grid = HeightGrid::flat(UVec2::new(4, 4), 0.);
+        let mut history = HeightGridHistory::new(1 << 20);
+        raise(&mut history, &mut grid, UVec2::new(1, 1));
+        raise(&mut history, &mut grid, UVec2::new(1, 1));
+        let edited = grid.clone();
+
+        history.undo(&mut grid);
+        history.undo(&mut grid);
+        assert_eq!(grid, HeightGrid::flat(UVec2::new(4, 4), 0.));
+        assert!(!history.can_undo());
+
+        let region = history.redo(&mut grid);
+        assert_eq!(grid.get(UVec2::new(1, 1)), 1.);
+        assert_eq!(region, Some(URect::new(1, 1, 2, 2)));
+        history.redo(&mut grid);
+        assert_eq!(grid, edited);
+        assert!(!history.can_redo());
+
+        // A new edit discards what could be redone.
+        history.undo(&mut grid);
+        raise(&mut history, &mut grid, UVec2::new(2, 2));
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(&mut grid), None);
+    }
+
+    #[test]
+    fn budget_drops_oldest_steps() {
+        let mut grid = HeightGrid::flat(UVec2::new(4, 4), 0.);
+        let step = HeightGridEdit::snapshot(&grid, URect::new(0, 0, 1, 1)).memory();
+        let mut history = HeightGridHistory::new(2 * step);
+        for x in 0..3 {
+            raise(&mut history, &mut grid, UVec2::new(x, 0));
+        }
+        assert_eq!(history.memory(), 2 * step);
+        assert!(history.undo(&mut grid).is_some());
+        assert!(history.undo(&mut grid).is_some());
+        assert_eq!(history.undo(&mut grid), None);
+        // The first edit can no longer be undone.
+        assert_eq!(grid.get(UVec2::new(0, 0)), 1.);
+        assert_eq!(grid.get(UVec2::new(1, 0)), 0.);
+    }
+
+    #[test]
+    fn end_stroke_enforces_budget() {
+        let mut grid = HeightGrid::flat(UVec2::new(4, 4), 0.);
+        let step = HeightGridEdit::snapshot(&grid, URect::new(0, 0, 1, 1)).memory();
+        let mut history = HeightGridHistory::new(2 * step);
+        raise(&mut history, &mut grid, UVec2::new(3, 3));
+        history.begin_stroke();
+        for x in 0..3 {
+            raise(&mut history, &mut grid, UVec2::new(x, 0));
+        }
+        history.end_stroke();
+        assert!(history.memory() <= history.memory_budget);
+        assert!(!history.can_undo());
+        assert_eq!(history.memory(), 0);
+    }
+}
@@ -0,0 +1,643 @@
+use std::collections::BinaryHeap;
+
+use bevy::math::{IVec2, Rect, URect, UVec2, Vec3};
+
+pub use crate::height_grid::D8_OFFSETS;
+use crate::{HeightGrid, MeshBuilder, PlacedGrid, height_grid::Lowest};
+
+/// Raises the points of depressions to the height at which they spill, with priority-flood.
+/// Water poured anywhere on the result flows, or pools flat, until it reaches the edge of the grid
+/// or a hole, which are left as they are.
+pub fn fill_depressions(grid: &HeightGrid) -> HeightGrid {
+    priority_flood(grid, |h| h)
+}
+
+/// Like [`fill_depressions`], but filled areas and flats slope very slightly towards their outlet,
+/// so every point that is not on the edge has a lower neighbor.
+pub fn fill_depressions_with_gradient(grid: &HeightGrid) -> HeightGrid {
+    priority_flood(grid, f32::next_up)
+}
+
+/// Floods the grid inwards from its outlets, lowest points first. Each point is raised to at least
+/// `raise` of the point it was reached from.
+fn priority_flood(grid: &HeightGrid, raise: impl Fn(f32) -> f32) -> HeightGrid {
+    let mut filled = grid.clone();
+    let mut done = vec![false; grid.heights.len()];
+    let mut open = BinaryHeap::new();
+    for y in 0..grid.size.y {
+        for x in 0..grid.size.x {
+            let xy = UVec2::new(x, y);
+            if grid.get(xy).is_nan() {
+                done[grid.index(xy)] = true;
+            } else if is_outlet(grid, xy) {
+                done[grid.index(xy)] = true;
+                open.push(Lowest(grid.get(xy), xy));
+            }
+        }
+    }
+    while let Some(Lowest(h, xy)) = open.pop() {
+        for neighbor in grid.neighbors(xy) {
+            let index = grid.index(neighbor);
+            if done[index] {
+                continue;
+            }
+            done[index] = true;
+            let neighbor_h = filled.heights[index].max(raise(h));
+            filled.heights[index] = neighbor_h;
+            open.push(Lowest(neighbor_h, neighbor));
+        }
+    }
+    filled
+}
+
+/// Whether water leaves the grid at `xy`, on its edge or next to a hole.
+fn is_outlet(grid: &HeightGrid, xy: UVec2) -> bool {
+    let size = grid.size;
+    xy.x == 0
+        || xy.y == 0
+        || xy.x == size.x - 1
+        || xy.y == size.y - 1
+        || grid
+            .neighbors(xy)
+            .any(|neighbor| grid.get(neighbor).is_nan())
+}
+
+/// How flow is distributed to the neighbors of a point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlowRouting {
+    /// All flow goes to the neighbor with the steepest descent.
+    #[default]
+    D8,
+    /// Flow follows the steepest descent over the eight triangular facets around the point,
+    /// split between the two neighbors of the facet (Tarboton's D-infinity).
+    DInfinity,
+}
+
+/// Flow directions, accumulation and rivers over a [`PlacedGrid`] with its depressions filled,
+/// with slopes and river polylines in world units.
+/// Water leaves the grid through its edge and the points next to holes.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{hydrology::*, *};
+/// // A valley along y = 0 sloping down towards -x.
+/// let valley = ValueFunctionHeightMap(|p: Vec2| p.y.abs() + 0.5 * p.x);
+/// let hydrology = Hydrology::new(
+///     PlacedGrid::from_heightmap(&valley, UVec2::new(33, 33), Rect::new(-50., -50., 50., 50.)),
+///     FlowRouting::D8,
+/// );
+/// let rivers = hydrology.river_polylines(100.);
+/// assert_eq!(rivers.len(), 1);
+/// assert!(rivers[0].iter().all(|p| p.y.abs() < 2.));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Hydrology {
+    /// Terrain with its depressions filled, see [`fill_depressions_with_gradient`].
+    /// Flow directions do not depend on its height scale, as all slopes scale alike.
+    pub terrain: PlacedGrid,
+    /// Number of grid points draining through each point, itself included, indexed like the grid's heights.
+    /// Zero for holes.
+    pub accumulation: Vec<f32>,
+    /// Downstream neighbors of each point with the fraction of its flow sent to them.
+    receivers: Vec<Vec<(UVec2, f32)>>,
+}
+
+impl Hydrology {
+    pub fn new(terrain: PlacedGrid, routing: FlowRouting) -> Self {
+        let mut hydrology = Self {
+            terrain: PlacedGrid {
+                grid: fill_depressions_with_gradient(&terrain.grid),
+                ..terrain
+            },
+            accumulation: Vec::new(),
+            receivers: Vec::new(),
+        };
+        hydrology.route(routing);
+        hydrology
+    }
+
+    /// Computes flow directions and accumulation.
+    fn route(&mut self, routing: FlowRouting) {
+        let grid = &self.terrain.grid;
+        self.receivers = (0..grid.size.y)
+            .flat_map(|y| (0..grid.size.x).map(move |x| UVec2::new(x, y)))
+            .map(|xy| match routing {
+                FlowRouting::D8 => self.d8_receivers(xy),
+                FlowRouting::DInfinity => self.d_infinity_receivers(xy),
+            })
+            .collect();
+        // Receivers are lower, so processing from the highest point accumulates all upstream flow first.
+        let mut order: Vec<usize> = (0..grid.heights.len()).collect();
+        order.sort_by(|&a, &b| grid.heights[b].total_cmp(&grid.heights[a]));
+        self.accumulation = grid
+            .heights
+            .iter()
+            .map(|h| if h.is_nan() { 0. } else { 1. })
+            .collect();
+        for index in order {
+            let flow = self.accumulation[index];
+            for &(receiver, fraction) in &self.receivers[index] {
+                let receiver = grid.index(receiver);
+                self.accumulation[receiver] += fraction * flow;
+            }
+        }
+    }
+
+    fn is_outlet(&self, xy: UVec2) -> bool {
+        self.terrain.grid.get(xy).is_nan() || is_outlet(&self.terrain.grid, xy)
+    }
+
+    fn d8_receivers(&self, xy: UVec2) -> Vec<(UVec2, f32)> {
+        if self.is_outlet(xy) {
+            return Vec::new();
+        }
+        let h = self.terrain.grid.get(xy);
+        let spacing = self.terrain.spacing();
+        self.terrain
+            .grid
+            .neighbors(xy)
+            .map(|neighbor| {
+                let distance = ((neighbor.as_vec2() - xy.as_vec2()) * spacing).length();
+                let drop = self.terrain.height_scale * (h - self.terrain.grid.get(neighbor));
+                (neighbor, drop / distance)
+            })
+            .filter(|(_, slope)| *slope > 0.)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(neighbor, _)| vec![(neighbor, 1.)])
+            .unwrap_or_default()
+    }
+
+    fn d_infinity_receivers(&self, xy: UVec2) -> Vec<(UVec2, f32)> {
+        if self.is_outlet(xy) {
+            return Vec::new();
+        }
+        let spacing = self.terrain.spacing();
+        let h = |p: IVec2| self.terrain.height_scale * self.terrain.grid.get(p.as_uvec2());
+        let center = xy.as_ivec2();
+        let e0 = h(center);
+        let mut steepest: Option<(f32, IVec2, IVec2, f32)> = None;
+        for cardinal in [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y] {
+            for side in [cardinal.perp(), -cardinal.perp()] {
+                let (e1, e2) = (h(center + cardinal), h(center + cardinal + side));
+                let d1 = (cardinal.as_vec2() * spacing).length();
+                let d2 = (side.as_vec2() * spacing).length();
+                let (s1, s2) = ((e0 - e1) / d1, (e1 - e2) / d2);
+                let max_angle = d2.atan2(d1);
+                let (angle, slope) = match s2.atan2(s1) {
+                    angle if angle < 0. => (0., s1),
+                    angle if angle > max_angle => (max_angle, (e0 - e2) / d1.hypot(d2)),
+                    angle => (angle, s1.hypot(s2)),
+                };
+                let fraction = angle / max_angle;
+                if slope > 0. && steepest.is_none_or(|(steepest, ..)| slope > steepest) {
+                    steepest = Some((slope, cardinal, side, fraction));
+                }
+            }
+        }
+        let Some((_, cardinal, side, fraction)) = steepest else {
+            return self.d8_receivers(xy);
+        };
+        [
+            (center + cardinal, 1. - fraction),
+            (center + cardinal + side, fraction),
+        ]
+        .into_iter()
+        .filter(|(_, fraction)| *fraction > 0.)
+        .map(|(p, fraction)| (p.as_uvec2(), fraction))
+        .collect()
+    }
+
+    /// Downstream neighbors of a point with the fraction of its flow sent to them.
+    /// Empty for holes and points on the edge of the grid or next to a hole.
+    pub fn receivers(&self, xy: UVec2) -> &[(UVec2, f32)] {
+        &self.receivers[self.terrain.grid.index(xy)]
+    }
+
+    /// Neighbor receiving most of a point's flow.
+    pub fn downstream(&self, xy: UVec2) -> Option<UVec2> {
+        self.receivers(xy)
+            .iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(receiver, _)| *receiver)
+    }
+
+    /// Offset to the [`Hydrology::downstream`] neighbor of each point, indexed like the grid's heights.
+    pub fn flow_directions(&self) -> Vec<Option<IVec2>> {
+        (0..self.terrain.grid.size.y)
+            .flat_map(|y| (0..self.terrain.grid.size.x).map(move |x| UVec2::new(x, y)))
+            .map(|xy| {
+                self.downstream(xy)
+                    .map(|downstream| downstream.as_ivec2() - xy.as_ivec2())
+            })
+            .collect()
+    }
+
+    /// Watershed of each point, indexed like the grid's heights: the points whose flow
+    /// leaves the grid through the same outlet share a label, numbered from 0 upwards.
+    /// Split flow follows the [`Hydrology::downstream`] neighbor. Each hole has a label of its own.
+    pub fn watersheds(&self) -> Vec<u32> {
+        let grid = &self.terrain.grid;
+        let mut order: Vec<usize> = (0..grid.heights.len()).collect();
+        order.sort_by(|&a, &b| grid.heights[a].total_cmp(&grid.heights[b]));
+        let mut labels = vec![0; grid.heights.len()];
+        let mut outlets = 0;
+        for index in order {
+            let xy = UVec2::new(index as u32 % grid.size.x, index as u32 / grid.size.x);
+            labels[index] = match self.downstream(xy) {
+                Some(downstream) => labels[grid.index(downstream)],
+                None => {
+                    outlets += 1;
+                    outlets - 1
+                }
+            };
+        }
+        labels
+    }
+
+    /// Rivers through the points with an accumulation of at least `threshold`,
+    /// split into polylines of grid points that run downstream between sources, confluences and the edge.
+    /// A confluence ends the polylines flowing into it and starts the one flowing out of it.
+    pub fn rivers(&self, threshold: f32) -> Vec<Vec<UVec2>> {
+        let grid = &self.terrain.grid;
+        let is_river = |xy: UVec2| self.accumulation[grid.index(xy)] >= threshold;
+        let mut tributaries = vec![0u32; grid.heights.len()];
+        for y in 0..grid.size.y {
+            for x in 0..grid.size.x {
+                let xy = UVec2::new(x, y);
+                if let Some(downstream) = self.downstream(xy)
+                    && is_river(xy)
+                    && is_river(downstream)
+                {
+                    tributaries[grid.index(downstream)] += 1;
+                }
+            }
+        }
+        let mut rivers = Vec::new();
+        for y in 0..grid.size.y {
+            for x in 0..grid.size.x {
+                let start = UVec2::new(x, y);
+                if !is_river(start) || tributaries[grid.index(start)] == 1 {
+                    continue;
+                }
+                let mut river = vec![start];
+                let mut current = start;
+                while let Some(downstream) = self.downstream(current)
+                    && is_river(downstream)
+                {
+                    river.push(downstream);
+                    if tributaries[grid.index(downstream)] != 1 {
+                        break;
+                    }
+                    current = downstream;
+                }
+                if river.len() > 1 {
+                    rivers.push(river);
+                }
+            }
+        }
+        rivers
+    }
+
+    /// [`Hydrology::rivers`] as world positions on the filled surface.
+    pub fn river_polylines(&self, threshold: f32) -> Vec<Vec<Vec3>> {
+        self.rivers(threshold)
+            .into_iter()
+            .map(|river| {
+                river
+                    .into_iter()
+                    .map(|xy| self.terrain.position(xy))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Lakes filling the depressions of a [`HeightGrid`] up to the level at which they spill,
+/// as found by [`fill_depressions`].
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{hydrology::*, *};
+/// // A crater whose rim is lowest towards +x.
+/// let crater = ValueFunctionHeightMap(|p: Vec2| 0.3 - (p.length() - 0.3).abs() - 0.1 * p.x);
+/// let grid = HeightGrid::from_heightmap(&crater, UVec2::new(65, 65));
+/// let lakes = Lakes::new(&grid, 0.01);
+/// assert_eq!(lakes.levels.len(), 1);
+/// assert_eq!(lakes.lake(UVec2::new(32, 32)), Some(0));
+/// let rect = Rect::new(-50., -50., 50., 50.);
+/// let water: Vec<Mesh> = lakes
+///     .mesh_builders(rect, 10.)
+///     .into_iter()
+///     .map(|builder| builder.with_orientation(Orientation::YUp).build())
+///     .collect();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Lakes {
+    pub size: UVec2,
+    /// Lake covering each point, indexed like the grid's heights, or `None` on dry land.
+    pub mask: Vec<Option<u32>>,
+    /// Water surface height of each lake, unscaled like the grid's heights.
+    pub levels: Vec<f32>,
+}
+
+impl Lakes {
+    /// Lakes of a grid, leaving out those never deeper than `min_depth`.
+    /// Lakes spilling into each other at different levels are kept apart.
+    pub fn new(grid: &HeightGrid, min_depth: f32) -> Self {
+        let filled = fill_depressions(grid);
+        let mut lakes = Self {
+            size: grid.size,
+            mask: vec![None; grid.heights.len()],
+            levels: Vec::new(),
+        };
+        let is_water = |index: usize| filled.heights[index] > grid.heights[index];
+        for y in 0..grid.size.y {
+            for x in 0..grid.size.x {
+                let start = UVec2::new(x, y);
+                let index = grid.index(start);
+                if !is_water(index) || lakes.mask[index].is_some() {
+                    continue;
+                }
+                let id = lakes.levels.len() as u32;
+                let level = filled.heights[index];
+                let mut points = vec![start];
+                let mut open = vec![start];
+                let mut depth: f32 = 0.;
+                lakes.mask[index] = Some(id);
+                while let Some(xy) = open.pop() {
+                    depth = depth.max(level - grid.get(xy));
+                    for neighbor in grid.neighbors(xy) {
+                        let index = grid.index(neighbor);
+                        if is_water(index)
+                            && filled.heights[index] == level
+                            && lakes.mask[index].is_none()
+                        {
+                            lakes.mask[index] = Some(id);
+                            points.push(neighbor);
+                            open.push(neighbor);
+                        }
+                    }
+                }
+                if depth > min_depth {
+                    lakes.levels.push(level);
+                } else {
+                    // Marked as visited, so the points are not revisited by later lakes.
+                    for xy in points {
+                        lakes.mask[grid.index(xy)] = Some(u32::MAX);
+                    }
+                }
+            }
+        }
+        for lake in &mut lakes.mask {
+            if *lake == Some(u32::MAX) {
+                *lake = None;
+            }
+        }
+        lakes
+    }
+
+    pub fn lake(&self, xy: UVec2) -> Option<u32> {
+        self.mask[(xy.x + xy.y * self.size.x) as usize]
+    }
+
+    /// Flat water surfaces, one per lake, for a grid spanning `rect` in the mesh's XY plane
+    /// with heights multiplied by `height_scale`.
+    ///
+    /// Each surface covers the grid cells with a corner in the lake, so its shore
+    /// lies under the terrain. UVs span the bounds of the lake.
+    pub fn mesh_builders(&self, rect: Rect, height_scale: f32) -> Vec<MeshBuilder> {
+        let spacing = rect.size() / (self.size - UVec2::ONE).as_vec2();
+        let mut bounds = vec![
+            URect {
+                min: UVec2::MAX,
+                max: UVec2::ZERO,
+            };
+            self.levels.len()
+        ];
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                if let Some(lake) = self.lake(UVec2::new(x, y)) {
+                    let bounds = &mut bounds[lake as usize];
+                    bounds.min = bounds.min.min(UVec2::new(x, y).saturating_sub(UVec2::ONE));
+                    bounds.max = bounds.max.max((UVec2::new(x, y) + 1).min(self.size - 1));
+                }
+            }
+        }
+        bounds
+            .into_iter()
+            .zip(&self.levels)
+            .enumerate()
+            .map(|(lake, (bounds, level))| {
+                let lake = Some(lake as u32);
+                let lake_rect = Rect::from_corners(
+                    rect.min + bounds.min.as_vec2() * spacing,
+                    rect.min + bounds.max.as_vec2() * spacing,
+                );
+                let size = bounds.size() + UVec2::ONE;
+                let mut builder = MeshBuilder::grid_rect(size, lake_rect);
+                for p in &mut builder.positions {
+                    p[2] = height_scale * level;
+                }
+                builder.indices.clear();
+                for y in 0..size.y - 1 {
+                    for x in 0..size.x - 1 {
+                        let corners = [(0, 0), (1, 0), (0, 1), (1, 1)];
+                        if corners.iter().any(|&(dx, dy)| {
+                            self.lake(bounds.min + UVec2::new(x + dx, y + dy)) == lake
+                        }) {
+                            builder
+                                .indices
+                                .extend(MeshBuilder::quad_indices(x, y, size.x));
+                        }
+                    }
+                }
+                // Only some quads are kept, so the builder is no longer a full grid.
+                builder.size = UVec2::ZERO;
+                builder
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use bevy::math::Vec2;
+
+    use super::*;
+    use crate::ValueFunctionHeightMap;
+
+    fn plane(size: UVec2, slope: Vec2) -> Hydrology {
+        Hydrology::new(
+            PlacedGrid::from_heightmap(
+                &ValueFunctionHeightMap(move |p: Vec2| 2. + slope.dot(p)),
+                size,
+                Rect::new(-1., -1., 1., 1.),
+            ),
+            FlowRouting::DInfinity,
+        )
+    }
+
+    #[test]
+    fn d_infinity_splits_flow_between_facet_neighbors() {
+        let hydrology = plane(UVec2::new(9, 9), Vec2::new(2., 1.));
+        let center = UVec2::new(4, 4);
+        // The descent is atan(1 / 2) from -x towards -y, between the west and south-west neighbors.
+        let fraction = 0.5f32.atan() / FRAC_PI_4;
+        let receivers = hydrology.receivers(center);
+        assert_eq!(receivers.len(), 2);
+        assert_eq!(receivers[0].0, UVec2::new(3, 4));
+        assert_eq!(receivers[1].0, UVec2::new(3, 3));
+        assert!((receivers[0].1 - (1. - fraction)).abs() < 1e-4);
+        assert!((receivers[1].1 - fraction).abs() < 1e-4);
+    }
+
+    #[test]
+    fn d_infinity_accumulation_conserves_flow() {
+        let size = UVec2::new(17, 13);
+        let hydrology = plane(size, Vec2::new(2., 1.));
+        // All water leaves through the edge.
+        let outflow: f32 = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+            .filter(|&xy| hydrology.receivers(xy).is_empty())
+            .map(|xy| hydrology.accumulation[hydrology.terrain.grid.index(xy)])
+            .sum();
+        assert!((outflow - size.element_product() as f32).abs() < 1e-3);
+        // Flow gathers downhill.
+        let accumulation =
+            |x, y| hydrology.accumulation[hydrology.terrain.grid.index(UVec2::new(x, y))];
+        assert!(accumulation(1, 6) > accumulation(8, 6));
+        assert!(accumulation(8, 6) > accumulation(15, 6));
+    }
+
+    #[test]
+    fn d_infinity_follows_diagonals() {
+        let size = UVec2::new(9, 9);
+        let hydrology = plane(size, Vec2::new(1., 1.));
+        let center = UVec2::new(4, 4);
+        assert_eq!(hydrology.receivers(center), &[(UVec2::new(3, 3), 1.)]);
+        // Each point along the diagonal drains the points up the diagonal from it, up to the edge.
+        let accumulation =
+            |i| hydrology.accumulation[hydrology.terrain.grid.index(UVec2::splat(i))];
+        for i in 1..8 {
+            assert!((accumulation(i) - (8 - i) as f32).abs() < 1e-3, "{i}");
+        }
+    }
+
+    #[test]
+    fn holes_drain_depressions() {
+        let size = UVec2::new(9, 9);
+        let bowl = ValueFunctionHeightMap(|p: Vec2| p.length());
+        let mut grid = HeightGrid::from_heightmap(&bowl, size);
+        let center = UVec2::new(4, 4);
+        *grid.get_mut(center) = f32::NAN;
+        let filled = fill_depressions(&grid);
+        for (h, filled) in grid.heights.iter().zip(&filled.heights) {
+            assert!(h == filled || h.is_nan() && filled.is_nan());
+        }
+        let hydrology = Hydrology::new(
+            PlacedGrid::new(grid.clone(), Rect::new(-1., -1., 1., 1.)),
+            FlowRouting::D8,
+        );
+        assert_eq!(hydrology.accumulation[grid.index(center)], 0.);
+        assert!(hydrology.receivers(center).is_empty());
+        // Points next to the hole are outlets, draining the inner part of the bowl.
+        let next_to_hole: f32 = grid
+            .neighbors(center)
+            .inspect(|&xy| assert!(hydrology.receivers(xy).is_empty()))
+            .map(|xy| hydrology.accumulation[grid.index(xy)])
+            .sum();
+        assert!(next_to_hole > 8.);
+        // Every other point drains through an outlet.
+        let outflow: f32 = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+            .filter(|&xy| hydrology.receivers(xy).is_empty())
+            .map(|xy| hydrology.accumulation[grid.index(xy)])
+            .sum();
+        assert_eq!(outflow, (size.element_product() - 1) as f32);
+        assert!(hydrology.accumulation.iter().all(|a| !a.is_nan()));
+    }
+
+    /// Two basins of depth `depth_a` and `depth_b` in a 9x5 grid, divided at x = 4 by a ridge
+    /// of height 1. The left basin spills over the ridge, the right one out of the grid at 0.5.
+    fn basins(depth_a: f32, depth_b: f32) -> HeightGrid {
+        let size = UVec2::new(9, 5);
+        let heights = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| (x, y)))
+            .map(|(x, y)| match (x, y) {
+                (8, 2) => 0.5,
+                (0 | 8, _) | (_, 0 | 4) => 3.,
+                (1..=3, _) => 1. - depth_a,
+                (4, _) => 1.,
+                _ => 0.5 - depth_b,
+            })
+            .collect();
+        HeightGrid::new(size, heights)
+    }
+
+    #[test]
+    fn shallow_lakes_are_left_out() {
+        let grid = basins(0.05, 0.4);
+        assert_eq!(Lakes::new(&grid, 0.01).levels.len(), 2);
+        let lakes = Lakes::new(&grid, 0.1);
+        assert_eq!(lakes.levels, [0.5]);
+        assert_eq!(lakes.lake(UVec2::new(2, 2)), None);
+        assert_eq!(lakes.lake(UVec2::new(6, 2)), Some(0));
+    }
+
+    #[test]
+    fn basins_with_different_levels_are_separate_lakes() {
+        let grid = basins(0.6, 0.4);
+        let lakes = Lakes::new(&grid, 0.01);
+        assert_eq!(lakes.levels, [1., 0.5]);
+        for y in 0..grid.size.y {
+            for x in 0..grid.size.x {
+                let expected = match (x, y) {
+                    (1..=3, 1..=3) => Some(0),
+                    (5..=7, 1..=3) => Some(1),
+                    _ => None,
+                };
+                assert_eq!(lakes.lake(UVec2::new(x, y)), expected, "({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn water_covers_flooded_cells() {
+        let grid = basins(0.6, 0.4);
+        let lakes = Lakes::new(&grid, 0.01);
+        // One unit between grid points, with the grid's corner at the origin.
+        let rect = Rect::new(0., 0., 8., 4.);
+        let builders = lakes.mesh_builders(rect, 10.);
+        assert_eq!(builders.len(), 2);
+        for (lake, builder) in builders.iter().enumerate() {
+            let mut cells: Vec<UVec2> = builder
+                .indices
+                .chunks_exact(3)
+                .map(|triangle| {
+                    let center = triangle
+                        .iter()
+                        .map(|&i| Vec3::from(builder.positions[i as usize]))
+                        .sum::<Vec3>()
+                        / 3.;
+                    assert_eq!(center.z, 10. * lakes.levels[lake]);
+                    center.truncate().floor().as_uvec2()
+                })
+                .collect();
+            cells.dedup();
+            // The cells with a corner in the lake.
+            let mut expected = Vec::new();
+            for y in 0..grid.size.y - 1 {
+                for x in 0..grid.size.x - 1 {
+                    let cell = UVec2::new(x, y);
+                    if [(0, 0), (1, 0), (0, 1), (1, 1)]
+                        .iter()
+                        .any(|&(dx, dy)| lakes.lake(cell + UVec2::new(dx, dy)) == Some(lake as u32))
+                    {
+                        expected.push(cell);
+                    }
+                }
+            }
+            assert_eq!(cells, expected);
+        }
+    }
+}
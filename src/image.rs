@@ -3,13 +3,101 @@ use bevy::{
     math::{UVec2, Vec2},
 };
 use image::{DynamicImage, ImageBuffer, Pixel, Rgba};
+use serde::{Deserialize, Serialize};
 
 use crate::{HeightMap, asset_loader::HeightMapLoaderError};
 
+/// How heights are stored in the pixels of an image.
+///
+/// Channels are read normalized to `[0, 1]`. The web map tile encodings
+/// decode to meters, which can be brought back in range with
+/// [`crate::asset_loader::HeightRemap`].
+/// ```
+/// use bevy_heightmap::image::{ColorChannel, HeightEncoding};
+/// // 0x01_86_A0 tenths of a meter above -10000m.
+/// let rgba = [0x01, 0x86, 0xA0, 0xFF].map(|c| c as f32 / 255.);
+/// assert!(HeightEncoding::TerrainRgb.decode(rgba).abs() < 1e-3);
+/// assert_eq!(HeightEncoding::Channel(ColorChannel::G).decode(rgba), 0x86 as f32 / 255.);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeightEncoding {
+    /// A single channel.
+    Channel(ColorChannel),
+    /// Rec. 709 luminance of the color channels.
+    Luminance,
+    /// Mapbox terrain-RGB: `-10000 + (R * 256 * 256 + G * 256 + B) * 0.1` meters.
+    TerrainRgb,
+    /// Terrarium: `R * 256 + G + B / 256 - 32768` meters.
+    Terrarium,
+}
+impl Default for HeightEncoding {
+    fn default() -> Self {
+        Self::Channel(ColorChannel::R)
+    }
+}
+impl HeightEncoding {
+    /// Decodes a height from the channels of a pixel, normalized to `[0, 1]`.
+    pub fn decode(self, rgba: [f32; 4]) -> f32 {
+        // Encoded bytes, rounded back from normalized channels.
+        let [r, g, b, _] = rgba.map(|c| (c * 255.).round());
+        match self {
+            Self::Channel(channel) => rgba[channel as usize],
+            Self::Luminance => 0.2126 * rgba[0] + 0.7152 * rgba[1] + 0.0722 * rgba[2],
+            Self::TerrainRgb => -10000. + (r * 65536. + g * 256. + b) * 0.1,
+            Self::Terrarium => r * 256. + g + b / 256. - 32768.,
+        }
+    }
+}
+
+/// Channel of an RGBA pixel, in the order the channels are stored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorChannel {
+    #[default]
+    R,
+    G,
+    B,
+    A,
+}
+
+/// Pixels of an image that are holes in the terrain, such as cave entrances or missing data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Holes {
+    /// Every pixel has a height.
+    #[default]
+    None,
+    /// Pixels with an alpha below one half.
+    Alpha,
+    /// Pixels whose decoded height is this value.
+    Sentinel(f32),
+}
+impl Holes {
+    /// Whether a pixel with normalized channels `rgba`, decoding to `h`, is a hole.
+    pub fn is_hole(self, rgba: [f32; 4], h: f32) -> bool {
+        match self {
+            Self::None => false,
+            Self::Alpha => rgba[3] < 0.5,
+            Self::Sentinel(sentinel) => h == sentinel,
+        }
+    }
+}
+
 pub struct ImageBufferHeightMap<P: Pixel, Container> {
     pub buffer: ImageBuffer<P, Container>,
     pub pixel_scale: Vec2,
     pub bounds: UVec2,
+    pub encoding: HeightEncoding,
+    pub holes: Holes,
+}
+impl<P: Pixel, Container> ImageBufferHeightMap<P, Container> {
+    pub fn with_encoding(mut self, encoding: HeightEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn with_holes(mut self, holes: Holes) -> Self {
+        self.holes = holes;
+        self
+    }
 }
 impl<P: Pixel> ImageBufferHeightMap<P, Vec<P::Subpixel>>
 where
@@ -24,6 +112,8 @@ where
             buffer,
             pixel_scale,
             bounds,
+            encoding: HeightEncoding::default(),
+            holes: Holes::None,
         }
     }
 }
@@ -39,19 +129,45 @@ impl ImageBufferHeightMap<Rgba<u8>, Vec<u8>> {
             buffer,
             pixel_scale,
             bounds,
+            encoding: HeightEncoding::default(),
+            holes: Holes::None,
         })
     }
 }
+impl ImageBufferHeightMap<Rgba<f32>, Vec<f32>> {
+    fn rgba(&self, p: Vec2) -> [f32; 4] {
+        let xy = (self.pixel_scale * (p + Vec2::ONE / 2.)).as_uvec2();
+        self.buffer.get_pixel(xy.x, self.bounds.y - xy.y).0
+    }
+}
 impl HeightMap for ImageBufferHeightMap<Rgba<f32>, Vec<f32>> {
     fn h(&self, p: Vec2) -> f32 {
-        let xy = (self.pixel_scale * (p + Vec2::ONE / 2.)).as_uvec2();
-        self.buffer.get_pixel(xy.x, self.bounds.y - xy.y).channels()[0]
+        self.encoding.decode(self.rgba(p))
+    }
+
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        let rgba = self.rgba(p);
+        let h = self.encoding.decode(rgba);
+        (!self.holes.is_hole(rgba, h)).then_some(h)
     }
 }
 
+impl ImageBufferHeightMap<Rgba<u8>, Vec<u8>> {
+    /// Channels of the pixel at `p`, normalized to `[0, 1]`.
+    fn rgba(&self, p: Vec2) -> [f32; 4] {
+        let xy = (self.pixel_scale * (p + Vec2::ONE / 2.)).as_uvec2();
+        let pixel = self.buffer.get_pixel(xy.x, self.bounds.y - xy.y);
+        pixel.0.map(|c| c as f32 / 255.)
+    }
+}
 impl HeightMap for ImageBufferHeightMap<Rgba<u8>, Vec<u8>> {
     fn h(&self, p: Vec2) -> f32 {
-        let xy = (self.pixel_scale * (p + Vec2::ONE / 2.)).as_uvec2();
-        self.buffer.get_pixel(xy.x, self.bounds.y - xy.y).channels()[0] as f32 / 255.
+        self.encoding.decode(self.rgba(p))
+    }
+
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        let rgba = self.rgba(p);
+        let h = self.encoding.decode(rgba);
+        (!self.holes.is_hole(rgba, h)).then_some(h)
     }
 }
@@ -1,31 +1,92 @@
 /// Simple crate for loading a heightmap .png image as a mesh.
+use std::sync::Arc;
+
 use asset_loader::HeightMapLoader;
 
 pub mod asset_loader;
+pub mod cell_grid;
+pub mod contour;
+#[cfg(feature = "displacement")]
+pub mod displacement;
+pub mod gis;
+pub mod height_grid;
+pub mod heightfield;
+pub mod history;
+pub mod hydrology;
 pub mod image;
 pub mod mesh_builder;
+pub mod parallel;
+pub mod pathfinding;
+pub mod placed_grid;
+pub mod planet;
+pub mod sculpt;
+pub mod statistics;
+pub mod streaming;
+pub mod terrain;
+pub mod visibility;
 
 use bevy::{
-    app::{App, Plugin},
+    app::{App, Plugin, PostUpdate, Update},
     asset::AssetApp,
-    math::{UVec2, Vec2},
+    ecs::schedule::IntoScheduleConfigs,
+    math::{Rect, UVec2, Vec2},
     mesh::Mesh,
 };
 
+#[cfg(feature = "displacement")]
+pub use crate::displacement::{HeightMapDisplacement, HeightMapMaterial};
+pub use crate::height_grid::HeightGrid;
 pub use crate::image::ImageBufferHeightMap;
-pub use crate::mesh_builder::MeshBuilder;
+pub use crate::mesh_builder::{MeshBuilder, Orientation, Shading, Triangulation};
+pub use crate::parallel::ParallelHeightMap;
+pub use crate::placed_grid::PlacedGrid;
+pub use crate::streaming::{TerrainChunk, TerrainChunkTask, TerrainStreamer};
+pub use crate::terrain::{Terrain, TerrainSource};
 
 /// A Heightmap is anything that provides a 2d value function `h()`.
-pub trait HeightMap: Sized {
+pub trait HeightMap {
     /// Compute the height value at a given point `p``.
     fn h(&self, p: Vec2) -> f32;
 
+    /// Height at `p`, or `None` over a hole. Meshes built from the heightmap leave out
+    /// the triangles touching a vertex over a hole.
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        Some(self.h(p))
+    }
+
     /// Builds a mesh from the heightmap.
     fn build_mesh(&self, size: UVec2) -> Mesh {
         let mut builder = MeshBuilder::grid(size);
         builder.update_z_positions(self);
         builder.build()
     }
+
+    /// Builds a mesh from the heightmap stretched over `rect` in world units.
+    fn build_mesh_rect(&self, size: UVec2, rect: Rect) -> Mesh {
+        let mut builder = MeshBuilder::grid_rect(size, rect);
+        builder.update_z_positions(self);
+        builder.build()
+    }
+}
+
+impl<H: HeightMap + ?Sized> HeightMap for Box<H> {
+    fn h(&self, p: Vec2) -> f32 {
+        (**self).h(p)
+    }
+
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        (**self).try_h(p)
+    }
+}
+
+impl<H: HeightMap + ?Sized> HeightMap for Arc<H> {
+    fn h(&self, p: Vec2) -> f32 {
+        (**self).h(p)
+    }
+
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        (**self).try_h(p)
+    }
 }
 
 /// Height map from value function;
@@ -46,6 +107,31 @@ impl<H: Fn(Vec2) -> f32> HeightMap for ValueFunctionHeightMap<H> {
     }
 }
 
+/// Height map with holes where a mask height map, such as a separate mask image, is below one half.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::*;
+/// let cave = ValueFunctionHeightMap(|p: Vec2| if p.distance(Vec2::new(0.2, 0.)) < 0.1 { 0. } else { 1. });
+/// let heightmap = MaskedHeightMap(ValueFunctionHeightMap(|p: Vec2| p.x), cave);
+/// assert_eq!(heightmap.try_h(Vec2::new(0.2, 0.)), None);
+/// let mesh = heightmap.build_mesh(UVec2::new(32, 32));
+/// assert!(mesh.indices().unwrap().len() < 31 * 31 * 6);
+/// ```
+pub struct MaskedHeightMap<H: HeightMap, M: HeightMap>(pub H, pub M);
+impl<H: HeightMap, M: HeightMap> HeightMap for MaskedHeightMap<H, M> {
+    fn h(&self, p: Vec2) -> f32 {
+        self.0.h(p)
+    }
+
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        if self.1.h(p) < 0.5 {
+            None
+        } else {
+            self.0.try_h(p)
+        }
+    }
+}
+
 /// Enables loading Meshes from images with `.hmp.png` extension.
 /// ```
 /// use bevy::prelude::*;
@@ -54,10 +140,29 @@ impl<H: Fn(Vec2) -> f32> HeightMap for ValueFunctionHeightMap<H> {
 ///     let mesh: Handle<Mesh> = asset_server.load("textures/terrain.hmp.png");
 /// }
 /// ```
+/// ESRI ASCII grids (`.asc`), SRTM tiles (`.hgt`) and, with the `tiff` feature, TIFF elevation rasters
+/// are loaded in meters with the [`gis`] loaders.
+/// Also keeps the meshes of [`Terrain`] entities up to date and streams [`TerrainStreamer`] chunks.
+/// With the `displacement` feature, also registers [`HeightMapMaterial`].
 pub struct HeightMapPlugin;
 impl Plugin for HeightMapPlugin {
     fn build(&self, app: &mut App) {
-        app.preregister_asset_loader::<HeightMapLoader>(HeightMapLoader::EXTENSIONS);
+        app.init_asset::<HeightGrid>()
+            .preregister_asset_loader::<HeightMapLoader>(HeightMapLoader::EXTENSIONS)
+            .register_asset_loader(gis::EsriAsciiLoader)
+            .register_asset_loader(gis::SrtmLoader)
+            .add_systems(
+                Update,
+                (TerrainStreamer::update, TerrainChunkTask::update).chain(),
+            )
+            .add_systems(PostUpdate, Terrain::update);
+        #[cfg(feature = "tiff")]
+        app.register_asset_loader(gis::TiffLoader);
+        #[cfg(feature = "displacement")]
+        {
+            bevy::asset::embedded_asset!(app, "shaders/displacement.wgsl");
+            app.add_plugins(bevy::pbr::MaterialPlugin::<HeightMapMaterial>::default());
+        }
     }
     fn finish(&self, app: &mut App) {
         app.init_asset_loader::<asset_loader::HeightMapLoader>();
@@ -1,21 +1,158 @@
 use bevy::{
-    asset::RenderAssetUsages,
-    math::{UVec2, Vec2},
-    mesh::{Indices, Mesh, PrimitiveTopology},
+    asset::{AssetId, Assets, RenderAssetUsages},
+    color::{Color, ColorToComponents},
+    math::{Rect, URect, UVec2, Vec2, Vec3},
+    mesh::{Indices, Mesh, PrimitiveTopology, VertexAttributeValues},
+    tasks::{ComputeTaskPool, ParallelSliceMut, TaskPool},
 };
 
-use crate::HeightMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{HeightMap, cell_grid::ATTRIBUTE_CELL_ID};
+
+/// Axis along which heights are emitted in generated meshes.
+///
+/// [`MeshBuilder`] works in Z-up space, with the grid in XY,
+/// and converts positions when the mesh is built.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Orientation {
+    /// Positions are `[x, y, h]`.
+    #[default]
+    ZUp,
+    /// Positions are `[x, h, -y]`, matching bevy's Y-up right-handed convention.
+    /// This is a rotation of the Z-up mesh, so winding and UVs are unchanged
+    /// and the top of the heightmap image faces `-Z`.
+    YUp,
+}
+impl Orientation {
+    /// Converts a position from Z-up space.
+    pub fn orient(self, p: [f32; 3]) -> [f32; 3] {
+        match self {
+            Self::ZUp => p,
+            Self::YUp => [p[0], p[2], -p[1]],
+        }
+    }
+
+    /// Converts a position back to Z-up space.
+    pub fn unorient(self, p: [f32; 3]) -> [f32; 3] {
+        match self {
+            Self::ZUp => p,
+            Self::YUp => [p[0], -p[2], p[1]],
+        }
+    }
+
+    /// Index of the height component of positions.
+    pub fn height_axis(self) -> usize {
+        match self {
+            Self::ZUp => 2,
+            Self::YUp => 1,
+        }
+    }
+
+    /// Converts the positions and normals of a Z-up mesh.
+    pub fn orient_mesh(self, mut mesh: Mesh) -> Mesh {
+        if self == Self::ZUp {
+            return mesh;
+        }
+        for attribute in [Mesh::ATTRIBUTE_POSITION, Mesh::ATTRIBUTE_NORMAL] {
+            if let Some(VertexAttributeValues::Float32x3(values)) = mesh.attribute_mut(attribute) {
+                for value in values.iter_mut() {
+                    *value = self.orient(*value);
+                }
+            }
+        }
+        mesh
+    }
+}
+
+/// How the quads of a grid are split into triangles.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::*;
+/// let mut builder = bevy_heightmap::MeshBuilder::grid(UVec2::new(8, 8))
+///     .with_triangulation(Triangulation::ShortestDiagonal);
+/// builder.update_z_positions(&ValueFunctionHeightMap(|p: Vec2| (p.x - p.y).abs()));
+/// let mesh = builder.build();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Triangulation {
+    /// Every quad is split along the same diagonal, see [`MeshBuilder::quad_indices`].
+    #[default]
+    Uniform,
+    /// Diagonals alternate in a checkerboard pattern.
+    Alternating,
+    /// Each quad is split along the diagonal with the smaller height difference,
+    /// following ridges and valleys.
+    /// Diagonals are not revisited by region updates such as [`MeshBuilder::update_mesh_region`].
+    ShortestDiagonal,
+    /// Each quad is split into four triangles around an added center vertex,
+    /// whose height is the average of the quad's corners.
+    /// Center vertices are stored after the grid's vertices, in quad order.
+    Fan,
+}
+
+/// How normals are computed for built meshes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shading {
+    /// Vertices are shared between triangles and normals are averaged, weighted by area.
+    #[default]
+    Smooth,
+    /// Every triangle gets its own vertices with the triangle's normal, for faceted low-poly terrain.
+    /// The mesh is not indexed, so region updates such as [`MeshBuilder::update_mesh_region`]
+    /// do not apply to it.
+    Flat,
+}
 
 /// Utility struct for building a mesh.
-#[derive(Default)]
 pub struct MeshBuilder {
     pub positions: Vec<[f32; 3]>,
     pub uvs: Vec<[f32; 2]>,
+    /// Used as the mesh's normals if there is one per position, and computed from triangles otherwise.
     pub normals: Vec<[f32; 3]>,
     pub indices: Vec<u32>,
+    /// Cell of each vertex, stored as [`ATTRIBUTE_CELL_ID`] if not empty.
+    pub cell_ids: Vec<u32>,
+    /// Area in XY covered by the heightmap, used to map positions to heightmap space.
+    pub rect: Rect,
+    /// Up axis of the built mesh.
+    pub orientation: Orientation,
+    /// Number of vertices along each axis for grids, zero otherwise.
+    pub size: UVec2,
+    /// Triangulation of grid quads, applied when the mesh is built.
+    /// Triangles with a vertex at a NaN height are then left out, leaving holes.
+    pub triangulation: Triangulation,
+    /// Normals of the built mesh.
+    pub shading: Shading,
+    /// Color of each face with [`Shading::Flat`] from its mean height and its slope,
+    /// the angle in radians between its normal and the up axis.
+    pub face_color: Option<fn(f32, f32) -> Color>,
+}
+
+impl Default for MeshBuilder {
+    fn default() -> Self {
+        Self {
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+            cell_ids: Vec::new(),
+            rect: Self::UNIT_RECT,
+            orientation: Orientation::ZUp,
+            size: UVec2::ZERO,
+            triangulation: Triangulation::Uniform,
+            shading: Shading::Smooth,
+            face_color: None,
+        }
+    }
 }
 
 impl MeshBuilder {
+    /// Unit square centered on the origin, the area covered by [`MeshBuilder::grid`].
+    pub const UNIT_RECT: Rect = Rect {
+        min: Vec2::splat(-0.5),
+        max: Vec2::splat(0.5),
+    };
+
     pub fn position_to_uv(p: &[f32; 3]) -> [f32; 2] {
         [0.5 + p[0], 0.5 - p[1]]
     }
@@ -36,8 +173,45 @@ impl MeshBuilder {
             Self::index(x, y + 1, w),
         ]
     }
+
+    /// Indices of a quad split along the diagonal from `(x + 1, y)` to `(x, y + 1)`.
+    pub fn flipped_quad_indices(x: u32, y: u32, w: u32) -> [u32; 6] {
+        [
+            // Bottom triangle
+            Self::index(x, y, w),
+            Self::index(x + 1, y, w),
+            Self::index(x, y + 1, w),
+            // Top triangle
+            Self::index(x + 1, y, w),
+            Self::index(x + 1, y + 1, w),
+            Self::index(x, y + 1, w),
+        ]
+    }
+
+    /// Indices of a quad split into four triangles around the vertex `center`.
+    pub fn fan_quad_indices(x: u32, y: u32, w: u32, center: u32) -> [u32; 12] {
+        let [a, b, c, d] =
+            [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)].map(|(x, y)| Self::index(x, y, w));
+        [a, b, center, b, c, center, c, d, center, d, a, center]
+    }
     /// Compute a grid mesh of quads according to size.
     pub fn grid(size: UVec2) -> Self {
+        Self::grid_rect(size, Self::UNIT_RECT)
+    }
+
+    /// Compute a grid mesh of quads according to size, spanning `rect` in world units.
+    /// The heightmap is stretched over `rect`, so X and Y spacing may differ.
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_heightmap::*;
+    /// // 2:1 terrain 200m wide, with 1m between vertices.
+    /// let builder = bevy_heightmap::MeshBuilder::grid_rect(
+    ///     UVec2::new(201, 101),
+    ///     Rect::new(-100., -50., 100., 50.),
+    /// );
+    /// assert_eq!(builder.positions[1], [-99., -50., 0.]);
+    /// ```
+    pub fn grid_rect(size: UVec2, rect: Rect) -> Self {
         let bounds = size - UVec2::ONE;
         let num_points = size.x as usize * size.y as usize;
         let num_quads = bounds.x as usize * bounds.y as usize;
@@ -46,11 +220,23 @@ impl MeshBuilder {
             uvs: Vec::with_capacity(num_points),
             normals: Vec::with_capacity(num_points),
             indices: Vec::with_capacity(num_quads * 6),
+            cell_ids: Vec::new(),
+            rect,
+            orientation: Orientation::ZUp,
+            size,
+            triangulation: Triangulation::Uniform,
+            shading: Shading::Smooth,
+            face_color: None,
         };
+        let (center, extents) = (rect.center(), rect.size());
         for y in 0..size.y {
             for x in 0..size.x {
                 let xy = UVec2::new(x, y).as_vec2() / bounds.as_vec2() - Vec2::splat(0.5);
-                builder.positions.push(xy.extend(0.0).to_array());
+                builder
+                    .uvs
+                    .push(Self::position_to_uv(&xy.extend(0.0).to_array()));
+                let position = center + xy * extents;
+                builder.positions.push(position.extend(0.0).to_array());
             }
         }
         for y in 0..bounds.y {
@@ -58,28 +244,685 @@ impl MeshBuilder {
                 builder.indices.extend(Self::quad_indices(x, y, size.x));
             }
         }
-        for p in builder.positions.iter_mut() {
-            builder.uvs.push(Self::position_to_uv(p));
-        }
         builder
     }
 
-    /// Updates z positions to use the heightmap.
-    pub fn update_z_positions<H: HeightMap>(&mut self, heightmap: &H) {
+    /// Compute a grid mesh of quads with `spacing` between vertices along X and Y,
+    /// with the first vertex at `origin`.
+    pub fn grid_with_spacing(size: UVec2, spacing: Vec2, origin: Vec2) -> Self {
+        let extents = spacing * (size - UVec2::ONE).as_vec2();
+        Self::grid_rect(size, Rect::from_corners(origin, origin + extents))
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn with_triangulation(mut self, triangulation: Triangulation) -> Self {
+        self.triangulation = triangulation;
+        self
+    }
+
+    pub fn with_shading(mut self, shading: Shading) -> Self {
+        self.shading = shading;
+        self
+    }
+
+    /// Colors faces from their height and slope, see [`MeshBuilder::face_color`].
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_heightmap::*;
+    /// let mut builder = bevy_heightmap::MeshBuilder::grid(UVec2::new(16, 16))
+    ///     .with_shading(Shading::Flat)
+    ///     .with_face_color(|height, slope| {
+    ///         if slope > 0.6 {
+    ///             Color::srgb(0.5, 0.5, 0.5)
+    ///         } else if height > 0.2 {
+    ///             Color::WHITE
+    ///         } else {
+    ///             Color::srgb(0.2, 0.6, 0.2)
+    ///         }
+    ///     });
+    /// builder.update_z_positions(&ValueFunctionHeightMap(|p: Vec2| (8. * p.x).sin() / 4.));
+    /// let mesh = builder.build();
+    /// assert_eq!(mesh.count_vertices(), 15 * 15 * 6);
+    /// ```
+    pub fn with_face_color(mut self, face_color: fn(f32, f32) -> Color) -> Self {
+        self.face_color = Some(face_color);
+        self
+    }
+
+    /// Replaces the indices of the grid's quads according to `triangulation`.
+    fn triangulate(&mut self) {
+        let size = self.size;
+        let bounds = size - UVec2::ONE;
+        let num_points = size.element_product();
+        self.indices.clear();
+        if self.triangulation == Triangulation::Fan {
+            for y in 0..bounds.y {
+                for x in 0..bounds.x {
+                    let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)]
+                        .map(|(x, y)| Self::index(x, y, size.x) as usize);
+                    let position = corners
+                        .iter()
+                        .map(|&i| Vec3::from(self.positions[i]))
+                        .sum::<Vec3>()
+                        / 4.;
+                    let uv = corners
+                        .iter()
+                        .map(|&i| Vec2::from(self.uvs[i]))
+                        .sum::<Vec2>()
+                        / 4.;
+                    self.positions.push(position.to_array());
+                    self.uvs.push(uv.to_array());
+                }
+            }
+        }
+        let h = |x: u32, y: u32| self.positions[Self::index(x, y, size.x) as usize][2];
+        let mut indices = Vec::with_capacity(self.indices.capacity());
+        for y in 0..bounds.y {
+            for x in 0..bounds.x {
+                match self.triangulation {
+                    Triangulation::Uniform => indices.extend(Self::quad_indices(x, y, size.x)),
+                    Triangulation::Alternating if (x + y) % 2 == 0 => {
+                        indices.extend(Self::quad_indices(x, y, size.x))
+                    }
+                    Triangulation::Alternating => {
+                        indices.extend(Self::flipped_quad_indices(x, y, size.x))
+                    }
+                    Triangulation::ShortestDiagonal => {
+                        let diagonal = (h(x, y) - h(x + 1, y + 1)).abs();
+                        let flipped_diagonal = (h(x + 1, y) - h(x, y + 1)).abs();
+                        if flipped_diagonal < diagonal {
+                            indices.extend(Self::flipped_quad_indices(x, y, size.x))
+                        } else {
+                            indices.extend(Self::quad_indices(x, y, size.x))
+                        }
+                    }
+                    Triangulation::Fan => {
+                        let center = num_points + Self::index(x, y, bounds.x);
+                        indices.extend(Self::fan_quad_indices(x, y, size.x, center))
+                    }
+                }
+            }
+        }
+        self.indices = indices;
+    }
+
+    /// Rectangle centered on the origin that fits in the unit square,
+    /// with the aspect ratio of a grid of `size` so that vertices are evenly spaced.
+    pub fn aspect_rect(size: UVec2) -> Rect {
+        let bounds = (size - UVec2::ONE).as_vec2();
+        Rect::from_center_size(Vec2::ZERO, bounds / bounds.max_element())
+    }
+
+    /// Updates z positions to use the heightmap, mapping `rect` to `[-0.5, 0.5]` in heightmap space.
+    /// Positions over holes, where [`HeightMap::try_h`] is `None`, get a NaN height.
+    pub fn update_z_positions<H: HeightMap + ?Sized>(&mut self, heightmap: &H) {
+        let (center, extents) = (self.rect.center(), self.rect.size());
         for p in self.positions.iter_mut() {
-            p[2] = heightmap.h(Vec2::new(p[0], p[1]));
+            p[2] = heightmap
+                .try_h((Vec2::new(p[0], p[1]) - center) / extents)
+                .unwrap_or(f32::NAN);
+        }
+    }
+
+    /// Drops the triangles with a vertex over a hole, marked by a NaN height,
+    /// and moves those vertices to zero height.
+    fn remove_holes(&mut self) {
+        let is_hole = |p: &[f32; 3]| p[2].is_nan();
+        if !self.positions.iter().any(is_hole) {
+            return;
+        }
+        let positions = &self.positions;
+        self.indices = self
+            .indices
+            .chunks_exact(3)
+            .filter(|triangle| !triangle.iter().any(|&i| is_hole(&positions[i as usize])))
+            .flatten()
+            .copied()
+            .collect();
+        for p in self.positions.iter_mut().filter(|p| is_hole(p)) {
+            p[2] = 0.;
         }
     }
 
+    /// Updates z positions to use the heightmap, evaluating equal chunks of the positions,
+    /// a few per thread, in parallel on the [`ComputeTaskPool`]. Positions over holes get a NaN height.
+    pub fn par_update_z_positions<H: HeightMap + Sync + ?Sized>(&mut self, heightmap: &H) {
+        let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let chunk_size = self
+            .positions
+            .len()
+            .div_ceil(4 * task_pool.thread_num().max(1))
+            .max(1);
+        let (center, extents) = (self.rect.center(), self.rect.size());
+        self.positions
+            .par_chunk_map_mut(task_pool, chunk_size, |_, positions| {
+                for p in positions.iter_mut() {
+                    p[2] = heightmap
+                        .try_h((Vec2::new(p[0], p[1]) - center) / extents)
+                        .unwrap_or(f32::NAN);
+                }
+            });
+    }
+
+    /// Resamples `heightmap` for the vertices in `region` of a mesh built from a grid of `size`,
+    /// recomputing only the normals affected by the change.
+    ///
+    /// `region` is in heightmap space and covers the grid points inside it,
+    /// rounded outward like [`crate::HeightGrid::region_around`].
+    /// `orientation` must match the one the mesh was built with.
+    /// Points over holes, where [`HeightMap::try_h`] is `None`, are set to zero height,
+    /// but triangles over holes are only added or removed by rebuilding the mesh.
+    /// Returns `false`, leaving the mesh untouched, if it is not an indexed grid mesh of `size`,
+    /// such as one built with [`Shading::Flat`].
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_heightmap::*;
+    /// let size = UVec2::new(64, 64);
+    /// let mut mesh = ValueFunctionHeightMap(|_| 0.).build_mesh(size);
+    /// let crater = ValueFunctionHeightMap(|p: Vec2| -(0.1 - p.length()).max(0.));
+    /// let region = Rect::from_center_half_size(Vec2::ZERO, Vec2::splat(0.1));
+    /// assert!(bevy_heightmap::MeshBuilder::update_mesh_region(
+    ///     &mut mesh,
+    ///     size,
+    ///     Orientation::ZUp,
+    ///     &crater,
+    ///     region,
+    /// ));
+    /// ```
+    pub fn update_mesh_region<H: HeightMap + ?Sized>(
+        mesh: &mut Mesh,
+        size: UVec2,
+        orientation: Orientation,
+        heightmap: &H,
+        region: Rect,
+    ) -> bool {
+        let region = Self::grid_region(size, region);
+        Self::update_grid_region(mesh, size, orientation, region, |_, p| {
+            heightmap.try_h(p).unwrap_or(f32::NAN)
+        })
+    }
+
+    /// Applies [`MeshBuilder::update_mesh_region`] to a mesh asset.
+    /// Returns `false` if the mesh does not exist or could not be updated.
+    pub fn update_mesh_asset<H: HeightMap + ?Sized>(
+        meshes: &mut Assets<Mesh>,
+        id: impl Into<AssetId<Mesh>>,
+        size: UVec2,
+        orientation: Orientation,
+        heightmap: &H,
+        region: Rect,
+    ) -> bool {
+        let Some(mut mesh) = meshes.get_mut(id) else {
+            return false;
+        };
+        Self::update_mesh_region(&mut mesh, size, orientation, heightmap, region)
+    }
+
+    /// Grid points (`min` inclusive, `max` exclusive) of a grid of `size` inside
+    /// the heightmap space `rect`, rounded outward and clamped to the grid.
+    pub(crate) fn grid_region(size: UVec2, rect: Rect) -> URect {
+        let bounds = (size - UVec2::ONE).as_vec2();
+        let grid_position = |p: Vec2| (p + Vec2::splat(0.5)) * bounds;
+        let min = grid_position(rect.min).ceil().clamp(Vec2::ZERO, bounds);
+        let max = grid_position(rect.max).floor().clamp(Vec2::ZERO, bounds);
+        URect::from_corners(min.as_uvec2(), max.as_uvec2() + UVec2::ONE)
+    }
+
+    /// Sets the heights of the vertices of a grid mesh of `size` in `region`
+    /// and recomputes the normals they affect.
+    /// `h` receives the grid point and the heightmap space position of each vertex.
+    ///
+    /// Holes are fixed when the mesh is built: vertices with a NaN height are set to zero height,
+    /// like those over holes, but no triangles are added or removed.
+    /// Returns `false` if the mesh is not indexed or its vertices do not match the grid,
+    /// as heights are written by grid index.
+    pub(crate) fn update_grid_region(
+        mesh: &mut Mesh,
+        size: UVec2,
+        orientation: Orientation,
+        region: URect,
+        h: impl Fn(UVec2, Vec2) -> f32,
+    ) -> bool {
+        let bounds = size - UVec2::ONE;
+        let num_points = size.element_product() as usize;
+        // `Triangulation::Fan` adds a center vertex per quad.
+        let num_fan_vertices = num_points + bounds.element_product() as usize;
+        if mesh.indices().is_none()
+            || ![num_points, num_fan_vertices].contains(&mesh.count_vertices())
+        {
+            return false;
+        }
+        let region = region.intersect(URect::from_corners(UVec2::ZERO, size));
+        if region.is_empty() {
+            return true;
+        }
+        let height_axis = orientation.height_axis();
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return false;
+        };
+        for y in region.min.y..region.max.y {
+            for x in region.min.x..region.max.x {
+                // Same heightmap space position as in `grid_rect`.
+                let xy = UVec2::new(x, y);
+                let p = xy.as_vec2() / bounds.as_vec2() - Vec2::splat(0.5);
+                let h = h(xy, p);
+                positions[Self::index(x, y, size.x) as usize][height_axis] =
+                    if h.is_nan() { 0. } else { h };
+            }
+        }
+        // Centers of quads triangulated with `Triangulation::Fan` follow their corners,
+        // except for those of quads left out over holes, which stay at zero height.
+        let num_points = num_points as u32;
+        let quad_region = URect::from_corners(region.min.saturating_sub(UVec2::ONE), region.max)
+            .intersect(URect::from_corners(UVec2::ZERO, bounds));
+        if let (Some(VertexAttributeValues::Float32x3(positions)), Some(Indices::U32(indices))) =
+            (mesh.attribute(Mesh::ATTRIBUTE_POSITION), mesh.indices())
+            && positions.len() > num_points as usize
+        {
+            let mut centers = Vec::with_capacity(quad_region.size().element_product() as usize);
+            for y in quad_region.min.y..quad_region.max.y {
+                for x in quad_region.min.x..quad_region.max.x {
+                    let quad = Self::index(x, y, bounds.x);
+                    let height = if Self::grid_quad_triangles(indices, size, quad).is_empty() {
+                        0.
+                    } else {
+                        [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)]
+                            .map(|(x, y)| {
+                                positions[Self::index(x, y, size.x) as usize][height_axis]
+                            })
+                            .iter()
+                            .sum::<f32>()
+                            / 4.
+                    };
+                    centers.push(((num_points + quad) as usize, height));
+                }
+            }
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+            else {
+                return false;
+            };
+            for (index, height) in centers {
+                positions[index][height_axis] = height;
+            }
+        }
+
+        // Normals depend on the heights of neighboring vertices.
+        let normal_region = URect::from_corners(
+            region.min.saturating_sub(UVec2::ONE),
+            (region.max + UVec2::ONE).min(size),
+        );
+        let normals = Self::grid_region_normals(mesh, size, normal_region);
+        let Some(VertexAttributeValues::Float32x3(mesh_normals)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            return false;
+        };
+        for (index, normal) in normals {
+            mesh_normals[index] = normal;
+        }
+        true
+    }
+
+    /// Triangles of a quad of a grid mesh of `size`.
+    ///
+    /// Triangles are stored in the order of their quads, still the case once those over holes
+    /// are removed, and the quad of a triangle is at the lowest coordinates of its corners.
+    fn grid_quad_triangles(indices: &[u32], size: UVec2, quad: u32) -> &[[u32; 3]] {
+        let bounds = size - UVec2::ONE;
+        let num_points = size.element_product();
+        let quad_of = |triangle: &[u32; 3]| {
+            let corner = triangle
+                .map(|i| match i.checked_sub(num_points) {
+                    // Center vertex of a `Triangulation::Fan` quad.
+                    Some(quad) => UVec2::new(quad % bounds.x, quad / bounds.x),
+                    None => UVec2::new(i % size.x, i / size.x),
+                })
+                .into_iter()
+                .reduce(UVec2::min)
+                .unwrap();
+            Self::index(corner.x, corner.y, bounds.x)
+        };
+        let (triangles, _) = indices.as_chunks::<3>();
+        let start = triangles.partition_point(|triangle| quad_of(triangle) < quad);
+        let end = start + triangles[start..].partition_point(|triangle| quad_of(triangle) == quad);
+        &triangles[start..end]
+    }
+
+    /// Computes area weighted normals for the vertices of a grid mesh in `region`,
+    /// including the center vertices of the quads between them.
+    fn grid_region_normals(mesh: &Mesh, size: UVec2, region: URect) -> Vec<(usize, [f32; 3])> {
+        let (Some(VertexAttributeValues::Float32x3(positions)), Some(Indices::U32(indices))) =
+            (mesh.attribute(Mesh::ATTRIBUTE_POSITION), mesh.indices())
+        else {
+            return Vec::new();
+        };
+        let bounds = size - UVec2::ONE;
+        let num_points = size.element_product();
+        // Sum the faces of the quads' triangles that contain the vertex.
+        let normal = |index: u32, quads: URect| {
+            let mut normal = Vec3::ZERO;
+            for qy in quads.min.y..quads.max.y {
+                for qx in quads.min.x..quads.max.x {
+                    let quad = Self::index(qx, qy, bounds.x);
+                    for triangle in Self::grid_quad_triangles(indices, size, quad) {
+                        if !triangle.contains(&index) {
+                            continue;
+                        }
+                        let [a, b, c] = triangle.map(|i| Vec3::from(positions[i as usize]));
+                        normal += (b - a).cross(c - a);
+                    }
+                }
+            }
+            normal.normalize_or_zero().to_array()
+        };
+        let mut normals = Vec::with_capacity(region.size().element_product() as usize);
+        for y in region.min.y..region.max.y {
+            for x in region.min.x..region.max.x {
+                let index = Self::index(x, y, size.x);
+                let quads = URect::new(
+                    x.saturating_sub(1),
+                    y.saturating_sub(1),
+                    (x + 1).min(bounds.x),
+                    (y + 1).min(bounds.y),
+                );
+                normals.push((index as usize, normal(index, quads)));
+            }
+        }
+        if positions.len() > num_points as usize {
+            let quad_region = region.intersect(URect::from_corners(UVec2::ZERO, bounds));
+            for y in quad_region.min.y..quad_region.max.y {
+                for x in quad_region.min.x..quad_region.max.x {
+                    let index = num_points + Self::index(x, y, bounds.x);
+                    let quad = URect::new(x, y, x + 1, y + 1);
+                    normals.push((index as usize, normal(index, quad)));
+                }
+            }
+        }
+        normals
+    }
+
     /// Produce a mesh from the accumulated attributes.
-    pub fn build(self) -> Mesh {
-        Mesh::new(
+    pub fn build(mut self) -> Mesh {
+        if self.triangulation != Triangulation::Uniform && self.size != UVec2::ZERO {
+            self.triangulate();
+        }
+        self.remove_holes();
+        let orientation = self.orientation;
+        let cell_ids = match self.shading {
+            Shading::Smooth => std::mem::take(&mut self.cell_ids),
+            Shading::Flat if self.cell_ids.is_empty() => Vec::new(),
+            Shading::Flat => self
+                .indices
+                .iter()
+                .map(|&i| self.cell_ids[i as usize])
+                .collect(),
+        };
+        let mut mesh = match self.shading {
+            // Normals provided by the builder, such as `cube_sphere`'s.
+            Shading::Smooth if self.normals.len() == self.positions.len() => Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals)
+            .with_inserted_indices(Indices::U32(self.indices)),
+            Shading::Smooth => Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs)
+            .with_inserted_indices(Indices::U32(self.indices))
+            .with_computed_area_weighted_normals(),
+            Shading::Flat => self.build_flat(),
+        };
+        if !cell_ids.is_empty() {
+            mesh.insert_attribute(ATTRIBUTE_CELL_ID, cell_ids);
+        }
+        orientation.orient_mesh(mesh)
+    }
+
+    /// Produce a non-indexed mesh with a vertex per triangle corner and face normals.
+    fn build_flat(self) -> Mesh {
+        let num_vertices = self.indices.len();
+        let mut positions = Vec::with_capacity(num_vertices);
+        let mut uvs = Vec::with_capacity(num_vertices);
+        let mut normals = Vec::with_capacity(num_vertices);
+        let mut colors = Vec::with_capacity(num_vertices);
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(self.positions[triangle[i] as usize]));
+            let normal = (b - a).cross(c - a).normalize_or_zero();
+            for &i in triangle {
+                positions.push(self.positions[i as usize]);
+                uvs.push(self.uvs[i as usize]);
+                normals.push(normal.to_array());
+            }
+            if let Some(face_color) = self.face_color {
+                let height = (a.z + b.z + c.z) / 3.;
+                let slope = normal.z.clamp(-1., 1.).acos();
+                colors.extend([face_color(height, slope).to_linear().to_f32_array(); 3]);
+            }
+        }
+        let mesh = Mesh::new(
             PrimitiveTopology::TriangleList,
             RenderAssetUsages::default(),
         )
-        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
-        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs)
-        .with_inserted_indices(Indices::U32(self.indices))
-        .with_computed_area_weighted_normals()
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        if colors.is_empty() {
+            mesh
+        } else {
+            mesh.with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HeightGrid;
+    use bevy::mesh::MeshVertexAttribute;
+
+    fn attribute(mesh: &Mesh, attribute: MeshVertexAttribute) -> &[[f32; 3]] {
+        match mesh.attribute(attribute) {
+            Some(VertexAttributeValues::Float32x3(values)) => values,
+            _ => panic!("missing {}", attribute.name),
+        }
+    }
+
+    /// Asserts that positions and normals match, up to rounding.
+    fn assert_meshes_match(a: &Mesh, b: &Mesh) {
+        for attribute_id in [Mesh::ATTRIBUTE_POSITION, Mesh::ATTRIBUTE_NORMAL] {
+            let (a, b) = (attribute(a, attribute_id), attribute(b, attribute_id));
+            assert_eq!(a.len(), b.len());
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                assert!(
+                    Vec3::from(*a).abs_diff_eq(Vec3::from(*b), 1e-5),
+                    "{} {i}: {a:?} != {b:?}",
+                    attribute_id.name
+                );
+            }
+        }
+    }
+
+    fn bumpy(size: UVec2) -> HeightGrid {
+        let heights = (0..size.element_product())
+            .map(|i| ((i * 37) % 11) as f32 / 20.)
+            .collect();
+        HeightGrid::new(size, heights)
+    }
+
+    fn indices(mesh: &Mesh) -> Vec<u32> {
+        mesh.indices().unwrap().iter().map(|i| i as u32).collect()
+    }
+
+    #[test]
+    fn shortest_diagonal_follows_smaller_height_difference() {
+        let size = UVec2::new(2, 2);
+        let build = |heights: Vec<f32>| {
+            HeightGrid::new(size, heights)
+                .mesh_builder(MeshBuilder::UNIT_RECT)
+                .with_triangulation(Triangulation::ShortestDiagonal)
+                .build()
+        };
+        // A raised corner at (0, 0) makes the diagonal through it the longer one.
+        assert_eq!(
+            indices(&build(vec![1., 0., 0., 0.])),
+            MeshBuilder::flipped_quad_indices(0, 0, 2)
+        );
+        assert_eq!(
+            indices(&build(vec![0., 1., 0., 0.])),
+            MeshBuilder::quad_indices(0, 0, 2)
+        );
+    }
+
+    #[test]
+    fn fan_adds_quad_centers() {
+        let size = UVec2::new(3, 3);
+        let grid = bumpy(size);
+        let mesh = grid
+            .mesh_builder(MeshBuilder::UNIT_RECT)
+            .with_triangulation(Triangulation::Fan)
+            .build();
+        let positions = attribute(&mesh, Mesh::ATTRIBUTE_POSITION);
+        assert_eq!(positions.len(), 9 + 4);
+        let indices = indices(&mesh);
+        assert_eq!(indices.len(), 4 * 12);
+        for y in 0..2 {
+            for x in 0..2 {
+                let quad = MeshBuilder::index(x, y, 2);
+                let center = Vec3::from(positions[9 + quad as usize]);
+                let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)]
+                    .map(|(x, y)| Vec3::from(positions[MeshBuilder::index(x, y, 3) as usize]));
+                assert!(center.abs_diff_eq(corners.iter().sum::<Vec3>() / 4., 1e-6));
+                assert_eq!(
+                    indices[quad as usize * 12..(quad as usize + 1) * 12],
+                    MeshBuilder::fan_quad_indices(x, y, 3, 9 + quad)
+                );
+            }
+        }
+        // Every triangle faces up.
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+            assert!((b - a).cross(c - a).z > 0.);
+        }
+    }
+
+    #[test]
+    fn region_update_with_holes_matches_build() {
+        let size = UVec2::new(8, 8);
+        let rect = Rect::new(-1., -1., 1., 1.);
+        let holes = [UVec2::new(3, 3), UVec2::new(4, 3), UVec2::new(0, 6)];
+        for triangulation in [Triangulation::Uniform, Triangulation::Fan] {
+            let build = |grid: &HeightGrid| {
+                grid.mesh_builder(rect)
+                    .with_triangulation(triangulation)
+                    .with_orientation(Orientation::YUp)
+                    .build()
+            };
+            let mut before = HeightGrid::flat(size, 0.);
+            for hole in holes {
+                *before.get_mut(hole) = f32::NAN;
+            }
+            let mut mesh = build(&before);
+            let after = bumpy(size);
+            let region = URect::new(0, 1, 7, 8);
+            let mut edited = before.clone();
+            for y in region.min.y..region.max.y {
+                for x in region.min.x..region.max.x {
+                    let xy = UVec2::new(x, y);
+                    if !edited.get(xy).is_nan() {
+                        *edited.get_mut(xy) = after.get(xy);
+                    }
+                }
+            }
+            edited.update_mesh(&mut mesh, Orientation::YUp, region);
+            assert_meshes_match(&mesh, &build(&edited));
+        }
+    }
+
+    #[test]
+    fn region_update_matches_build() {
+        let size = UVec2::new(9, 7);
+        let rect = Rect::new(-2., -1., 2., 1.);
+        for triangulation in [Triangulation::Uniform, Triangulation::Fan] {
+            for orientation in [Orientation::ZUp, Orientation::YUp] {
+                let build = |grid: &HeightGrid| {
+                    grid.mesh_builder(rect)
+                        .with_triangulation(triangulation)
+                        .with_orientation(orientation)
+                        .build()
+                };
+                let before = HeightGrid::flat(size, 0.);
+                let mut mesh = build(&before);
+                let after = bumpy(size);
+                // Edit a region touching the edge of the grid.
+                let region = URect::new(0, 2, 5, 7);
+                let mut edited = before.clone();
+                for y in region.min.y..region.max.y {
+                    for x in region.min.x..region.max.x {
+                        let xy = UVec2::new(x, y);
+                        *edited.get_mut(xy) = after.get(xy);
+                    }
+                }
+                assert!(edited.update_mesh(&mut mesh, orientation, region));
+                assert_meshes_match(&mesh, &build(&edited));
+            }
+        }
+    }
+
+    #[test]
+    fn region_update_rounds_heightmap_rect_outward() {
+        let size = UVec2::new(5, 5);
+        let mut mesh = HeightGrid::flat(size, 0.).build_mesh();
+        let raised = crate::ValueFunctionHeightMap(|_| 1.);
+        // Grid points are 0.25 apart, so this covers x in 1..=2 and y in 2..=2.
+        let rect = Rect::new(-0.3, -0.05, 0.05, 0.05);
+        assert!(MeshBuilder::update_mesh_region(
+            &mut mesh,
+            size,
+            Orientation::ZUp,
+            &raised,
+            rect
+        ));
+        let positions = attribute(&mesh, Mesh::ATTRIBUTE_POSITION);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let inside = (1..=2).contains(&x) && y == 2;
+                let expected = if inside { 1. } else { 0. };
+                assert_eq!(
+                    positions[MeshBuilder::index(x, y, size.x) as usize][2],
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn region_update_rejects_flat_shading() {
+        let grid = bumpy(UVec2::new(6, 5));
+        let mut mesh = HeightGrid::flat(grid.size, 0.)
+            .mesh_builder(MeshBuilder::UNIT_RECT)
+            .with_shading(Shading::Flat)
+            .build();
+        let before = mesh.clone();
+        assert!(!grid.update_mesh(&mut mesh, Orientation::ZUp, grid.rect()));
+        assert!(!MeshBuilder::update_mesh_region(
+            &mut mesh,
+            grid.size,
+            Orientation::ZUp,
+            &grid,
+            MeshBuilder::UNIT_RECT
+        ));
+        assert_meshes_match(&mesh, &before);
     }
 }
@@ -0,0 +1,62 @@
+use bevy::{
+    math::UVec2,
+    mesh::Mesh,
+    tasks::{AsyncComputeTaskPool, Task, TaskPool},
+};
+
+use crate::{HeightMap, MeshBuilder};
+
+/// Parallel and asynchronous mesh generation for heightmaps that can be shared between threads.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::*;
+/// let heightmap = ValueFunctionHeightMap(|p: Vec2| ((20. * p.x).sin() + (20. * p.y).sin()) / 2.);
+/// let mesh: Mesh = heightmap.par_build_mesh(UVec2::new(256, 256));
+/// assert_eq!(mesh.count_vertices(), 256 * 256);
+/// ```
+pub trait ParallelHeightMap: HeightMap + Sync {
+    /// Builds a mesh from the heightmap, evaluating heights on the [`bevy::tasks::ComputeTaskPool`].
+    fn par_build_mesh(&self, size: UVec2) -> Mesh {
+        let mut builder = MeshBuilder::grid(size);
+        builder.par_update_z_positions(self);
+        builder.build()
+    }
+
+    /// Builds a mesh from the heightmap on the [`AsyncComputeTaskPool`],
+    /// for use from systems without stalling the frame.
+    /// ```
+    /// use bevy::{prelude::*, tasks::{Task, futures::check_ready}};
+    /// use bevy_heightmap::*;
+    ///
+    /// #[derive(Component)]
+    /// struct MeshTask(Task<Mesh>);
+    ///
+    /// fn spawn(mut commands: Commands) {
+    ///     let heightmap = ValueFunctionHeightMap(|p: Vec2| p.x * p.y);
+    ///     commands.spawn(MeshTask(heightmap.build_mesh_async(UVec2::new(2048, 2048))));
+    /// }
+    ///
+    /// fn poll(
+    ///     mut tasks: Query<(Entity, &mut MeshTask)>,
+    ///     mut meshes: ResMut<Assets<Mesh>>,
+    ///     mut commands: Commands,
+    /// ) {
+    ///     for (entity, mut task) in tasks.iter_mut() {
+    ///         if let Some(mesh) = check_ready(&mut task.0) {
+    ///             commands
+    ///                 .entity(entity)
+    ///                 .remove::<MeshTask>()
+    ///                 .insert(Mesh3d(meshes.add(mesh)));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn build_mesh_async(self, size: UVec2) -> Task<Mesh>
+    where
+        Self: Sized + Send + 'static,
+    {
+        AsyncComputeTaskPool::get_or_init(TaskPool::default)
+            .spawn(async move { self.build_mesh(size) })
+    }
+}
+impl<H: HeightMap + Sync + ?Sized> ParallelHeightMap for H {}
@@ -0,0 +1,282 @@
+use std::collections::BinaryHeap;
+
+use bevy::math::{UVec2, Vec3};
+
+use crate::{PlacedGrid, height_grid::Lowest};
+
+/// Finds walkable paths between the points of a [`PlacedGrid`] with A*,
+/// moving to any of the eight neighbors of a point. Costs and waypoints are in world units.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{pathfinding::*, *};
+/// // A ridge along x = 0 with a low pass in the middle.
+/// let hill = ValueFunctionHeightMap(|p: Vec2| (1. - 10. * p.x.abs()).max(0.) * (5. * p.y.abs()).min(1.));
+/// let terrain = PlacedGrid::from_heightmap(&hill, UVec2::new(33, 33), Rect::new(-50., -50., 50., 50.))
+///     .with_height_scale(20.);
+/// let pathfinder = Pathfinder::new(terrain).with_max_slope(30f32.to_radians());
+/// let path = pathfinder
+///     .find_path(Vec3::new(-40., 30., 0.), Vec3::new(40., 30., 0.))
+///     .unwrap();
+/// assert!(path.iter().all(|p| p.z < 10.));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pathfinder {
+    /// Terrain to walk on, whose orientation is the up axis of positions given to
+    /// and returned by [`Pathfinder::find_path`].
+    pub terrain: PlacedGrid,
+    /// Steepest climbable angle in radians between two neighboring points.
+    pub max_slope: f32,
+    /// Extra cost per unit of distance at a slope of one, so steeper steps cost more.
+    pub slope_cost: f32,
+    /// Points that cannot be walked on, indexed like the grid's heights. Empty if none are blocked.
+    pub blocked: Vec<bool>,
+}
+
+impl Pathfinder {
+    pub fn new(terrain: PlacedGrid) -> Self {
+        Self {
+            terrain,
+            max_slope: std::f32::consts::FRAC_PI_4,
+            slope_cost: 1.,
+            blocked: Vec::new(),
+        }
+    }
+
+    pub fn with_max_slope(mut self, max_slope: f32) -> Self {
+        self.max_slope = max_slope;
+        self
+    }
+
+    pub fn with_slope_cost(mut self, slope_cost: f32) -> Self {
+        self.slope_cost = slope_cost;
+        self
+    }
+
+    /// Blocks the points set in `blocked`, indexed like the grid's heights.
+    pub fn with_blocked(mut self, blocked: Vec<bool>) -> Self {
+        assert_eq!(blocked.len(), self.terrain.grid.heights.len());
+        self.blocked = blocked;
+        self
+    }
+
+    /// Whether a point is blocked, or a hole in the grid.
+    pub fn is_blocked(&self, xy: UVec2) -> bool {
+        self.terrain.grid.get(xy).is_nan()
+            || self
+                .blocked
+                .get(self.terrain.grid.index(xy))
+                .copied()
+                .unwrap_or(false)
+    }
+
+    pub fn set_blocked(&mut self, xy: UVec2, blocked: bool) {
+        if self.blocked.is_empty() {
+            self.blocked = vec![false; self.terrain.grid.heights.len()];
+        }
+        let index = self.terrain.grid.index(xy);
+        self.blocked[index] = blocked;
+    }
+
+    /// Cost of stepping between neighboring grid points, or `None` if the step is not walkable.
+    pub fn step_cost(&self, from: UVec2, to: UVec2) -> Option<f32> {
+        if self.is_blocked(to) {
+            return None;
+        }
+        let terrain = &self.terrain;
+        let distance = terrain
+            .plane_position(from)
+            .distance(terrain.plane_position(to));
+        let rise = terrain.height_scale * (terrain.grid.get(to) - terrain.grid.get(from));
+        let slope = rise.abs() / distance;
+        if slope.atan() > self.max_slope {
+            return None;
+        }
+        Some(distance.hypot(rise) * (1. + self.slope_cost * slope))
+    }
+
+    /// Path of world positions from the grid point nearest to `start` to the one nearest to `goal`,
+    /// or `None` if the goal is unreachable.
+    pub fn find_path(&self, start: Vec3, goal: Vec3) -> Option<Vec<Vec3>> {
+        let terrain = &self.terrain;
+        let path =
+            self.find_grid_path(terrain.nearest_point(start), terrain.nearest_point(goal))?;
+        Some(path.into_iter().map(|xy| terrain.position(xy)).collect())
+    }
+
+    /// Path of grid points from `start` to `goal`, both included, or `None` if the goal is unreachable.
+    pub fn find_grid_path(&self, start: UVec2, goal: UVec2) -> Option<Vec<UVec2>> {
+        if self.is_blocked(start) || self.is_blocked(goal) {
+            return None;
+        }
+        let goal_position = self.terrain.plane_position(goal);
+        // Horizontal distance never exceeds the cost of the steps covering it.
+        let heuristic = |xy: UVec2| self.terrain.plane_position(xy).distance(goal_position);
+        let (costs, came_from) = self.search(start, Some(goal), heuristic);
+        if costs[self.terrain.grid.index(goal)].is_infinite() {
+            return None;
+        }
+        let mut path = vec![goal];
+        let mut current = goal;
+        while current != start {
+            current = came_from[self.terrain.grid.index(current)];
+            path.push(current);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Cost of the cheapest path from every grid point to `goal` with Dijkstra's algorithm,
+    /// indexed like the grid's heights. Unreachable points have an infinite cost.
+    ///
+    /// Steps are evaluated from `goal`, so this assumes climbing and descending cost the same,
+    /// which holds for [`Pathfinder::step_cost`].
+    pub fn cost_field(&self, goal: UVec2) -> Vec<f32> {
+        if self.is_blocked(goal) {
+            return vec![f32::INFINITY; self.terrain.grid.heights.len()];
+        }
+        self.search(goal, None, |_| 0.).0
+    }
+
+    /// A* from `start`, stopping at `goal` if any, returning the cost and predecessor of each point.
+    fn search(
+        &self,
+        start: UVec2,
+        goal: Option<UVec2>,
+        heuristic: impl Fn(UVec2) -> f32,
+    ) -> (Vec<f32>, Vec<UVec2>) {
+        let mut costs = vec![f32::INFINITY; self.terrain.grid.heights.len()];
+        let mut came_from = vec![UVec2::ZERO; self.terrain.grid.heights.len()];
+        let mut open = BinaryHeap::new();
+        costs[self.terrain.grid.index(start)] = 0.;
+        // Ordered by the estimated cost of the whole path.
+        open.push(Lowest(heuristic(start), (0., start)));
+        while let Some(Lowest(_, (cost, xy))) = open.pop() {
+            if Some(xy) == goal {
+                break;
+            }
+            if cost > costs[self.terrain.grid.index(xy)] {
+                continue;
+            }
+            for neighbor in self.neighbors(xy) {
+                let Some(step) = self.step_cost(xy, neighbor) else {
+                    continue;
+                };
+                let index = self.terrain.grid.index(neighbor);
+                let cost = cost + step;
+                if cost < costs[index] {
+                    costs[index] = cost;
+                    came_from[index] = xy;
+                    open.push(Lowest(cost + heuristic(neighbor), (cost, neighbor)));
+                }
+            }
+        }
+        (costs, came_from)
+    }
+
+    /// Neighbors of a grid point, without cutting the corners of blocked points.
+    fn neighbors(&self, xy: UVec2) -> impl Iterator<Item = UVec2> + '_ {
+        self.terrain.grid.neighbors(xy).filter(move |neighbor| {
+            !self.is_blocked(UVec2::new(neighbor.x, xy.y))
+                && !self.is_blocked(UVec2::new(xy.x, neighbor.y))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Rect;
+
+    use super::*;
+    use crate::HeightGrid;
+
+    /// Pathfinder over a flat 5x5 grid with one unit between points.
+    fn flat() -> Pathfinder {
+        Pathfinder::new(PlacedGrid::new(
+            HeightGrid::flat(UVec2::splat(5), 0.),
+            Rect::new(-2., -2., 2., 2.),
+        ))
+    }
+
+    #[test]
+    fn cost_field_measures_distance_to_goal() {
+        let pathfinder = flat().with_slope_cost(0.);
+        let costs = pathfinder.cost_field(UVec2::new(2, 2));
+        let cost = |x, y| costs[pathfinder.terrain.grid.index(UVec2::new(x, y))];
+        assert_eq!(cost(2, 2), 0.);
+        assert!((cost(4, 2) - 2.).abs() < 1e-5);
+        assert!((cost(0, 0) - 2. * 2f32.sqrt()).abs() < 1e-5);
+        assert!((cost(4, 1) - (1. + 2f32.sqrt())).abs() < 1e-5);
+    }
+
+    #[test]
+    fn blocked_points_are_avoided() {
+        // A wall along x = 2 with a gap at the top.
+        let mut blocked = vec![false; 25];
+        for y in 0..4 {
+            blocked[y * 5 + 2] = true;
+        }
+        let mut pathfinder = flat().with_blocked(blocked);
+        let (start, goal) = (UVec2::new(0, 0), UVec2::new(4, 0));
+        let path = pathfinder.find_grid_path(start, goal).unwrap();
+        assert!(path.contains(&UVec2::new(2, 4)));
+        assert!(path.iter().all(|&xy| !pathfinder.is_blocked(xy)));
+        assert!(pathfinder.cost_field(goal)[pathfinder.terrain.grid.index(start)].is_finite());
+
+        pathfinder.set_blocked(UVec2::new(2, 4), true);
+        assert_eq!(pathfinder.find_grid_path(start, goal), None);
+        assert!(pathfinder.cost_field(goal)[pathfinder.terrain.grid.index(start)].is_infinite());
+        pathfinder.set_blocked(UVec2::new(2, 4), false);
+        assert!(pathfinder.find_grid_path(start, goal).is_some());
+    }
+
+    #[test]
+    fn steep_steps_are_rejected() {
+        // A cliff one unit high between x = 1 and x = 2: 45 degrees straight across, 35 diagonally.
+        let heights = (0..25).map(|i| if i % 5 < 2 { 0. } else { 1. }).collect();
+        let pathfinder = Pathfinder::new(PlacedGrid::new(
+            HeightGrid::new(UVec2::splat(5), heights),
+            Rect::new(-2., -2., 2., 2.),
+        ));
+        let (start, goal) = (UVec2::new(0, 2), UVec2::new(4, 2));
+        let steep = pathfinder.clone().with_max_slope(30f32.to_radians());
+        assert_eq!(steep.step_cost(UVec2::new(1, 2), UVec2::new(2, 2)), None);
+        assert_eq!(steep.step_cost(UVec2::new(1, 2), UVec2::new(2, 3)), None);
+        assert_eq!(steep.find_grid_path(start, goal), None);
+        let path = pathfinder
+            .with_max_slope(50f32.to_radians())
+            .find_grid_path(start, goal)
+            .unwrap();
+        assert_eq!(path.len(), 5);
+    }
+
+    #[test]
+    fn diagonals_do_not_cut_corners() {
+        let mut pathfinder = flat();
+        pathfinder.set_blocked(UVec2::new(1, 0), true);
+        let path = pathfinder
+            .find_grid_path(UVec2::ZERO, UVec2::new(1, 1))
+            .unwrap();
+        assert_eq!(path, [UVec2::ZERO, UVec2::new(0, 1), UVec2::new(1, 1)]);
+        pathfinder.set_blocked(UVec2::new(0, 1), true);
+        assert_eq!(
+            pathfinder.find_grid_path(UVec2::ZERO, UVec2::new(1, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn holes_cannot_be_start_or_goal() {
+        let mut pathfinder = flat();
+        let hole = UVec2::new(3, 3);
+        *pathfinder.terrain.grid.get_mut(hole) = f32::NAN;
+        assert!(pathfinder.is_blocked(hole));
+        assert_eq!(pathfinder.find_grid_path(hole, UVec2::ZERO), None);
+        assert_eq!(pathfinder.find_grid_path(UVec2::ZERO, hole), None);
+        assert!(pathfinder.cost_field(hole).iter().all(|c| c.is_infinite()));
+        // Paths around the hole do not step on it.
+        let path = pathfinder
+            .find_grid_path(UVec2::new(2, 2), UVec2::new(4, 4))
+            .unwrap();
+        assert!(!path.contains(&hole));
+    }
+}
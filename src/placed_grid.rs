@@ -0,0 +1,95 @@
+use bevy::math::{Rect, UVec2, Vec2, Vec3};
+
+use crate::{HeightGrid, HeightMap, Orientation};
+
+/// [`HeightGrid`] placed in the world, for queries that take and return world positions.
+///
+/// The grid is placed like a mesh built with [`HeightGrid::mesh_builder`] over `rect`,
+/// with heights multiplied by `height_scale` and `orientation` as the up axis.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::*;
+/// let ramp = ValueFunctionHeightMap(|p: Vec2| p.x + 0.5);
+/// let terrain = PlacedGrid::from_heightmap(&ramp, UVec2::new(11, 11), Rect::new(0., 0., 100., 100.))
+///     .with_height_scale(10.)
+///     .with_orientation(Orientation::YUp);
+/// assert_eq!(terrain.position(UVec2::new(10, 0)), Vec3::new(100., 10., 0.));
+/// assert_eq!(terrain.nearest_point(Vec3::new(52., 0., -38.)), UVec2::new(5, 4));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PlacedGrid {
+    pub grid: HeightGrid,
+    /// Area in the mesh's XY plane covered by the grid.
+    pub rect: Rect,
+    /// Multiplier for heights.
+    pub height_scale: f32,
+    /// Up axis of world positions.
+    pub orientation: Orientation,
+}
+
+impl PlacedGrid {
+    pub fn new(grid: HeightGrid, rect: Rect) -> Self {
+        Self {
+            grid,
+            rect,
+            height_scale: 1.,
+            orientation: Orientation::ZUp,
+        }
+    }
+
+    /// Places `heightmap` sampled at a grid of `size`.
+    pub fn from_heightmap<H: HeightMap + ?Sized>(heightmap: &H, size: UVec2, rect: Rect) -> Self {
+        Self::new(HeightGrid::from_heightmap(heightmap, size), rect)
+    }
+
+    pub fn with_height_scale(mut self, height_scale: f32) -> Self {
+        self.height_scale = height_scale;
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Distance between grid points along x and y.
+    pub fn spacing(&self) -> Vec2 {
+        self.rect.size() / (self.grid.size - UVec2::ONE).as_vec2()
+    }
+
+    /// Position of a grid point in the mesh's XY plane.
+    pub fn plane_position(&self, xy: UVec2) -> Vec2 {
+        self.grid.rect_position(xy, self.rect)
+    }
+
+    /// World position of a grid point.
+    pub fn position(&self, xy: UVec2) -> Vec3 {
+        let p = self
+            .plane_position(xy)
+            .extend(self.height_scale * self.grid.get(xy));
+        Vec3::from(self.orientation.orient(p.to_array()))
+    }
+
+    /// Z-up position in the mesh's space of a world position.
+    pub fn unorient(&self, p: Vec3) -> Vec3 {
+        Vec3::from(self.orientation.unorient(p.to_array()))
+    }
+
+    /// Nearest grid point to a world position, ignoring its height.
+    pub fn nearest_point(&self, p: Vec3) -> UVec2 {
+        let bounds = (self.grid.size - UVec2::ONE).as_vec2();
+        self.grid
+            .rect_grid_position(self.unorient(p).truncate(), self.rect)
+            .round()
+            .clamp(Vec2::ZERO, bounds)
+            .as_uvec2()
+    }
+
+    /// Terrain height below a position in the mesh's XY plane, interpolated between grid points.
+    /// NaN next to holes.
+    pub fn height_at(&self, p: Vec2) -> f32 {
+        let p = ((p - self.rect.center()) / self.rect.size())
+            .clamp(Vec2::splat(-0.5), Vec2::splat(0.5));
+        self.height_scale * self.grid.h(p)
+    }
+}
@@ -0,0 +1,265 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use bevy::{
+    math::{UVec2, Vec2, Vec3},
+    platform::collections::HashMap,
+};
+
+use crate::{HeightMap, MeshBuilder};
+
+/// Heights of a planet's surface, sampled by unit direction from its center.
+pub trait SphereHeightMap {
+    fn h(&self, direction: Vec3) -> f32;
+}
+
+/// Sphere height map from value function.
+pub struct ValueFunctionSphereHeightMap<H: Fn(Vec3) -> f32>(pub H);
+impl<H: Fn(Vec3) -> f32> SphereHeightMap for ValueFunctionSphereHeightMap<H> {
+    fn h(&self, direction: Vec3) -> f32 {
+        self.0(direction)
+    }
+}
+
+/// Wraps a [`HeightMap`] around the sphere with an equirectangular projection:
+/// longitude along x and latitude along y, with the poles on the z axis.
+///
+/// This matches the UVs of [`MeshBuilder::cube_sphere`], so an equirectangular image
+/// can be used both for heights and as a texture.
+pub struct Equirectangular<H: HeightMap>(pub H);
+impl<H: HeightMap> SphereHeightMap for Equirectangular<H> {
+    fn h(&self, direction: Vec3) -> f32 {
+        self.0.h(equirectangular_position(direction))
+    }
+}
+
+/// Heightmap space position of a direction in the projection of [`Equirectangular`].
+pub fn equirectangular_position(direction: Vec3) -> Vec2 {
+    let longitude = direction.y.atan2(direction.x);
+    let latitude = direction.z.clamp(-1., 1.).asin();
+    Vec2::new(longitude / TAU, latitude / PI)
+}
+
+/// Face of a cube centered on the origin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+impl CubeFace {
+    pub const ALL: [Self; 6] = [
+        Self::PosX,
+        Self::NegX,
+        Self::PosY,
+        Self::NegY,
+        Self::PosZ,
+        Self::NegZ,
+    ];
+
+    /// Outward normal and the axes of the face's x and y coordinates, with `x.cross(y) == normal`.
+    pub fn axes(self) -> [Vec3; 3] {
+        match self {
+            Self::PosX => [Vec3::X, Vec3::Y, Vec3::Z],
+            Self::NegX => [Vec3::NEG_X, Vec3::NEG_Y, Vec3::Z],
+            Self::PosY => [Vec3::Y, Vec3::NEG_X, Vec3::Z],
+            Self::NegY => [Vec3::NEG_Y, Vec3::X, Vec3::Z],
+            Self::PosZ => [Vec3::Z, Vec3::X, Vec3::Y],
+            Self::NegZ => [Vec3::NEG_Z, Vec3::X, Vec3::NEG_Y],
+        }
+    }
+
+    /// Unit direction through a point of the face, given in `[-0.5, 0.5]`.
+    pub fn direction(self, p: Vec2) -> Vec3 {
+        let [normal, x, y] = self.axes();
+        (0.5 * normal + p.x * x + p.y * y).normalize()
+    }
+
+    /// Face a direction points through, and the point of the face in `[-0.5, 0.5]`.
+    pub fn from_direction(direction: Vec3) -> (Self, Vec2) {
+        let abs = direction.abs();
+        let face = if abs.x >= abs.y && abs.x >= abs.z {
+            if direction.x >= 0. {
+                Self::PosX
+            } else {
+                Self::NegX
+            }
+        } else if abs.y >= abs.z {
+            if direction.y >= 0. {
+                Self::PosY
+            } else {
+                Self::NegY
+            }
+        } else if direction.z >= 0. {
+            Self::PosZ
+        } else {
+            Self::NegZ
+        };
+        let [normal, x, y] = face.axes();
+        let p = Vec2::new(direction.dot(x), direction.dot(y)) * 0.5 / direction.dot(normal);
+        (face, p)
+    }
+}
+
+/// Six heightmaps, one per [`CubeFace`] in the order of [`CubeFace::ALL`],
+/// each sampled in the face's coordinates.
+pub struct CubeFaces<H: HeightMap>(pub [H; 6]);
+impl<H: HeightMap> SphereHeightMap for CubeFaces<H> {
+    fn h(&self, direction: Vec3) -> f32 {
+        let (face, p) = CubeFace::from_direction(direction);
+        self.0[face as usize].h(p)
+    }
+}
+
+impl MeshBuilder {
+    /// Compute a sphere of `radius` from the six faces of a cube, each a grid of `resolution` vertices
+    /// per side, displaced outward by the heightmap.
+    ///
+    /// Normals are computed from the heightmap, so they are continuous across face edges.
+    /// UVs are equirectangular like [`Equirectangular`], and vertices of triangles crossing
+    /// the `u = 0` seam are duplicated with `u > 1`, so textures should repeat horizontally.
+    /// With an odd `resolution`, triangles around the poles share a vertex whose `u` is arbitrary.
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_heightmap::{planet::*, *};
+    /// let heightmap = Equirectangular(ValueFunctionHeightMap(|p: Vec2| (10. * p.x).sin() * 0.1));
+    /// let mesh = bevy_heightmap::MeshBuilder::cube_sphere(16, 1., &heightmap).build();
+    /// assert!(mesh.count_vertices() >= 6 * 16 * 16);
+    /// ```
+    pub fn cube_sphere<H: SphereHeightMap + ?Sized>(
+        resolution: u32,
+        radius: f32,
+        heightmap: &H,
+    ) -> Self {
+        let face_grid = Self::grid(UVec2::splat(resolution));
+        let num_points = face_grid.positions.len();
+        let mut builder = Self {
+            positions: Vec::with_capacity(6 * num_points),
+            uvs: Vec::with_capacity(6 * num_points),
+            normals: Vec::with_capacity(6 * num_points),
+            indices: Vec::with_capacity(6 * face_grid.indices.len()),
+            ..Self::default()
+        };
+        let surface = |direction: Vec3| direction * (radius + heightmap.h(direction));
+        // Half the angle between neighboring vertices at the center of a face.
+        let epsilon = FRAC_PI_2 / (resolution - 1) as f32 / 2.;
+        for face in CubeFace::ALL {
+            let start = builder.positions.len() as u32;
+            for p in face_grid.positions.iter() {
+                let direction = face.direction(Vec2::new(p[0], p[1]));
+                builder.positions.push(surface(direction).to_array());
+                let tangent = direction.any_orthonormal_vector();
+                let bitangent = direction.cross(tangent);
+                let offset = |axis: Vec3| {
+                    surface((direction + epsilon * axis).normalize())
+                        - surface((direction - epsilon * axis).normalize())
+                };
+                let normal = offset(tangent)
+                    .cross(offset(bitangent))
+                    .normalize_or(direction);
+                builder.normals.push(normal.to_array());
+                let uv = equirectangular_position(direction);
+                builder
+                    .uvs
+                    .push(Self::position_to_uv(&uv.extend(0.).to_array()));
+            }
+            builder
+                .indices
+                .extend(face_grid.indices.iter().map(|index| start + index));
+        }
+        builder.split_uv_seam();
+        builder
+    }
+
+    /// Duplicates the vertices of triangles that wrap around `u = 0`, shifting their `u` by one.
+    fn split_uv_seam(&mut self) {
+        let mut shifted: HashMap<u32, u32> = HashMap::default();
+        for t in (0..self.indices.len()).step_by(3) {
+            let u = Vec3::from_array([0, 1, 2].map(|i| self.uvs[self.indices[t + i] as usize][0]));
+            if u.max_element() - u.min_element() <= 0.5 {
+                continue;
+            }
+            for i in t..t + 3 {
+                let index = self.indices[i] as usize;
+                if self.uvs[index][0] >= 0.5 {
+                    continue;
+                }
+                self.indices[i] = *shifted.entry(index as u32).or_insert_with(|| {
+                    self.positions.push(self.positions[index]);
+                    self.normals.push(self.normals[index]);
+                    let [u, v] = self.uvs[index];
+                    self.uvs.push([u + 1., v]);
+                    (self.positions.len() - 1) as u32
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bumpy() -> impl SphereHeightMap {
+        ValueFunctionSphereHeightMap(|d: Vec3| 0.1 * (5. * d.x).sin() * (3. * d.y + d.z).cos())
+    }
+
+    #[test]
+    fn face_edges_match() {
+        let resolution = 8;
+        let builder = MeshBuilder::cube_sphere(resolution, 1., &bumpy());
+        let num_points = (resolution * resolution) as usize;
+        let face_of = |i: usize| i / num_points;
+        // Vertices duplicated by `split_uv_seam` come after the faces.
+        let vertices = 0..6 * num_points;
+        for i in vertices.clone() {
+            let xy = UVec2::new(i as u32 % resolution, (i % num_points) as u32 / resolution);
+            let on_edge = xy.min_element() == 0 || xy.max_element() == resolution - 1;
+            let shared: Vec<usize> = vertices
+                .clone()
+                .filter(|&j| {
+                    face_of(j) != face_of(i)
+                        && Vec3::from(builder.positions[i])
+                            .abs_diff_eq(Vec3::from(builder.positions[j]), 1e-5)
+                })
+                .collect();
+            assert_eq!(on_edge, !shared.is_empty(), "vertex {i} at {xy}");
+            for j in shared {
+                let (a, b) = (builder.normals[i], builder.normals[j]);
+                assert!(
+                    Vec3::from(a).abs_diff_eq(Vec3::from(b), 1e-4),
+                    "normals of {i} and {j}: {a:?} != {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn seam_split_leaves_no_wrapping_triangles() {
+        let builder = MeshBuilder::cube_sphere(10, 1., &bumpy());
+        assert!(builder.positions.len() > 6 * 10 * 10);
+        for triangle in builder.indices.chunks_exact(3) {
+            let u = triangle.iter().map(|&i| builder.uvs[i as usize][0]);
+            let (min, max) = u.fold((f32::MAX, f32::MIN), |(min, max), u| {
+                (min.min(u), max.max(u))
+            });
+            assert!(max - min <= 0.5, "{triangle:?} spans u from {min} to {max}");
+        }
+    }
+
+    #[test]
+    fn from_direction_inverts_direction() {
+        for face in CubeFace::ALL {
+            for y in -4..=4 {
+                for x in -4..=4 {
+                    let p = Vec2::new(x as f32, y as f32) / 9.;
+                    let (found, q) = CubeFace::from_direction(face.direction(p));
+                    assert_eq!(found, face);
+                    assert!(q.abs_diff_eq(p, 1e-5), "{face:?}: {q} != {p}");
+                }
+            }
+        }
+    }
+}
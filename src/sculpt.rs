@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use bevy::math::{FloatExt, URect, UVec2, Vec2};
+
+use crate::{HeightGrid, HeightMap};
+
+/// How a brush's influence decreases from its center (`t = 0`) to its edge (`t = 1`).
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Falloff {
+    /// Full strength over the whole brush.
+    Constant,
+    /// Linear decrease to zero at the edge.
+    Linear,
+    /// Smoothstep decrease to zero at the edge.
+    #[default]
+    Smooth,
+    /// Hemisphere shaped falloff.
+    Sphere,
+    /// User supplied curve mapping `t` to a weight.
+    Curve(fn(f32) -> f32),
+}
+impl Falloff {
+    /// Weight of the brush at normalized distance `t` from its center.
+    pub fn weight(&self, t: f32) -> f32 {
+        if t > 1. {
+            return 0.;
+        }
+        match self {
+            Self::Constant => 1.,
+            Self::Linear => 1. - t,
+            Self::Smooth => 1. - t * t * (3. - 2. * t),
+            Self::Sphere => (1. - t * t).sqrt(),
+            Self::Curve(curve) => curve(t),
+        }
+    }
+}
+
+/// Operation a brush performs on the heights under it.
+#[derive(Clone, Debug)]
+pub enum BrushKind {
+    /// Adds `strength` to heights.
+    Raise,
+    /// Subtracts `strength` from heights.
+    Lower,
+    /// Blends heights toward the average of their neighbors.
+    Smooth,
+    /// Blends heights toward a target height.
+    Flatten(f32),
+    /// Adds value noise with the given frequency (in heightmap space) and seed.
+    Noise { frequency: f32, seed: u32 },
+    /// Adds a heightmap stretched over the brush's bounding square.
+    Stamp(Arc<HeightGrid>),
+}
+
+/// Brush for sculpting a [`HeightGrid`].
+///
+/// Positions and radii are in heightmap space, the same space as [`HeightMap::h`].
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{sculpt::*, *};
+/// let mut grid = HeightGrid::flat(UVec2::new(16, 16), 0.);
+/// let mut mesh = grid.build_mesh();
+/// let brush = Brush::new(BrushKind::Raise, 0.2);
+/// let region = brush.apply(&mut grid, Vec2::ZERO);
+/// grid.update_mesh(&mut mesh, Orientation::ZUp, region);
+/// assert!(grid.h(Vec2::ZERO) > 0.);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Brush {
+    pub kind: BrushKind,
+    pub radius: f32,
+    /// Height change (or blend factor for `Smooth` and `Flatten`) per application.
+    pub strength: f32,
+    pub falloff: Falloff,
+}
+impl Brush {
+    pub fn new(kind: BrushKind, radius: f32) -> Self {
+        Self {
+            kind,
+            radius,
+            strength: 0.1,
+            falloff: Falloff::default(),
+        }
+    }
+
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Region of grid points modified by applying the brush at `center`.
+    pub fn region(&self, grid: &HeightGrid, center: Vec2) -> URect {
+        grid.region_around(center, self.radius)
+    }
+
+    /// Applies the brush at `center`, returning the modified region. Holes are left untouched.
+    pub fn apply(&self, grid: &mut HeightGrid, center: Vec2) -> URect {
+        let region = self.region(grid, center);
+        if region.is_empty() {
+            return region;
+        }
+        // Smoothing reads neighbors, so read from a copy of the heights before this application.
+        let source = match self.kind {
+            BrushKind::Smooth => Some(Self::snapshot(grid, region)),
+            _ => None,
+        };
+        for y in region.min.y..region.max.y {
+            for x in region.min.x..region.max.x {
+                let xy = UVec2::new(x, y);
+                let p = grid.position(xy);
+                let weight = self.falloff.weight(p.distance(center) / self.radius);
+                if weight <= 0. {
+                    continue;
+                }
+                let amount = self.strength * weight;
+                let h = grid.get_mut(xy);
+                if h.is_nan() {
+                    continue;
+                }
+                match &self.kind {
+                    BrushKind::Raise => *h += amount,
+                    BrushKind::Lower => *h -= amount,
+                    BrushKind::Smooth => {
+                        let (origin, source) = source.as_ref().unwrap();
+                        let average = Self::neighbor_average(source, xy - *origin);
+                        *h = h.lerp(average, amount.min(1.));
+                    }
+                    BrushKind::Flatten(target) => *h = h.lerp(*target, amount.min(1.)),
+                    BrushKind::Noise { frequency, seed } => {
+                        *h += amount * value_noise(p * *frequency, *seed);
+                    }
+                    BrushKind::Stamp(stamp) => {
+                        *h += amount * stamp.h((p - center) / (2. * self.radius));
+                    }
+                }
+            }
+        }
+        region
+    }
+
+    /// Copy of the heights in `region` and the points around it, with the position of its first point.
+    fn snapshot(grid: &HeightGrid, region: URect) -> (UVec2, HeightGrid) {
+        let min = region.min.saturating_sub(UVec2::ONE);
+        let max = (region.max + UVec2::ONE).min(grid.size);
+        let heights = (min.y..max.y)
+            .flat_map(|y| (min.x..max.x).map(move |x| grid.get(UVec2::new(x, y))))
+            .collect();
+        (min, HeightGrid::new(max - min, heights))
+    }
+
+    /// Average of a point and its neighbors, skipping holes. The snapshot only ends short of the
+    /// points around the brush region on the edge of the grid, where there are no neighbors either.
+    fn neighbor_average(grid: &HeightGrid, xy: UVec2) -> f32 {
+        let min = xy.saturating_sub(UVec2::ONE);
+        let max = (xy + UVec2::ONE).min(grid.size - UVec2::ONE);
+        let mut sum = 0.;
+        let mut count = 0.;
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let h = grid.get(UVec2::new(x, y));
+                if !h.is_nan() {
+                    sum += h;
+                    count += 1.;
+                }
+            }
+        }
+        sum / count
+    }
+}
+
+/// Hashes a lattice point to a value in `[-1, 1]`.
+fn lattice_value(x: i32, y: i32, seed: u32) -> f32 {
+    let mut n = (x as u32)
+        .wrapping_mul(0x27d4_eb2d)
+        .wrapping_add((y as u32).wrapping_mul(0x1656_67b1))
+        .wrapping_add(seed.wrapping_mul(0x9e37_79b9));
+    n = (n ^ (n >> 15)).wrapping_mul(0x85eb_ca6b);
+    n = (n ^ (n >> 13)).wrapping_mul(0xc2b2_ae35);
+    n ^= n >> 16;
+    n as f32 / u32::MAX as f32 * 2. - 1.
+}
+
+/// Smoothly interpolated value noise in `[-1, 1]`.
+fn value_noise(p: Vec2, seed: u32) -> f32 {
+    let cell = p.floor();
+    let t = p - cell;
+    let t = t * t * (Vec2::splat(3.) - 2. * t);
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let bottom = lattice_value(x, y, seed).lerp(lattice_value(x + 1, y, seed), t.x);
+    let top = lattice_value(x, y + 1, seed).lerp(lattice_value(x + 1, y + 1, seed), t.x);
+    bottom.lerp(top, t.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_matches_whole_grid_average() {
+        let size = UVec2::new(8, 8);
+        let heights = (0..64).map(|i| ((i * 37) % 11) as f32).collect();
+        let mut grid = HeightGrid::new(size, heights);
+        let original = grid.clone();
+        let brush = Brush::new(BrushKind::Smooth, 0.3)
+            .with_strength(1.)
+            .with_falloff(Falloff::Constant);
+        // Over the corner, so the brush region touches the edge of the grid.
+        let center = original.position(UVec2::ZERO);
+        let region = brush.apply(&mut grid, center);
+        assert!(!region.is_empty() && region.min == UVec2::ZERO);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let xy = UVec2::new(x, y);
+                let inside = xy.cmplt(region.max).all()
+                    && original.position(xy).distance(center) <= brush.radius;
+                let h = original.get(xy);
+                let expected = if inside {
+                    h.lerp(Brush::neighbor_average(&original, xy), 1.)
+                } else {
+                    h
+                };
+                assert_eq!(grid.get(xy), expected, "{xy}");
+            }
+        }
+    }
+
+    #[test]
+    fn brushes_leave_holes_alone() {
+        let hole = UVec2::new(4, 4);
+        for kind in [BrushKind::Smooth, BrushKind::Raise, BrushKind::Flatten(1.)] {
+            let mut grid = HeightGrid::flat(UVec2::new(9, 9), 0.5);
+            *grid.get_mut(hole) = f32::NAN;
+            Brush::new(kind, 0.3).apply(&mut grid, Vec2::ZERO);
+            assert!(grid.get(hole).is_nan());
+            let holes = grid.heights.iter().filter(|h| h.is_nan()).count();
+            assert_eq!(holes, 1);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Displaces a flat `MeshBuilder::grid` along +Z by the red channel of a height texture.
+#import bevy_pbr::{
+    mesh_functions,
+    view_transformations::position_world_to_clip,
+}
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::prepass_io::{Vertex, VertexOutput}
+#else
+#import bevy_pbr::forward_io::{Vertex, VertexOutput}
+#endif
+
+struct HeightMapDisplacement {
+    height_scale: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100) var<uniform> displacement: HeightMapDisplacement;
+@group(#{MATERIAL_BIND_GROUP}) @binding(101) var height_texture: texture_2d<f32>;
+
+// Reads a single texel, clamping to the texture bounds.
+fn load_height(texel: vec2<i32>) -> f32 {
+    let bounds = vec2<i32>(textureDimensions(height_texture)) - vec2<i32>(1);
+    return textureLoad(height_texture, clamp(texel, vec2<i32>(0), bounds), 0).r;
+}
+
+// Bilinearly samples the height at a uv coordinate.
+// Uses `textureLoad` so that non-filterable formats such as `Rgba32Float` are supported.
+fn sample_height(uv: vec2<f32>) -> f32 {
+    let bounds = vec2<f32>(textureDimensions(height_texture)) - vec2<f32>(1.0);
+    let xy = clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0)) * bounds;
+    let base = vec2<i32>(floor(xy));
+    let t = fract(xy);
+    let h00 = load_height(base);
+    let h10 = load_height(base + vec2<i32>(1, 0));
+    let h01 = load_height(base + vec2<i32>(0, 1));
+    let h11 = load_height(base + vec2<i32>(1, 1));
+    return displacement.height_scale * mix(mix(h00, h10, t.x), mix(h01, h11, t.x), t.y);
+}
+
+// Computes the local space normal from central differences of the height texture.
+fn sample_normal(uv: vec2<f32>) -> vec3<f32> {
+    let texel = 1.0 / (vec2<f32>(textureDimensions(height_texture)) - vec2<f32>(1.0));
+    let dx = sample_height(uv + vec2<f32>(texel.x, 0.0)) - sample_height(uv - vec2<f32>(texel.x, 0.0));
+    let dy = sample_height(uv + vec2<f32>(0.0, texel.y)) - sample_height(uv - vec2<f32>(0.0, texel.y));
+    // Local y increases as uv.y decreases.
+    return normalize(vec3<f32>(-dx / (2.0 * texel.x), dy / (2.0 * texel.y), 1.0));
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+#ifdef VERTEX_UVS_A
+    let uv = vertex.uv;
+#else
+    let uv = vec2<f32>(0.5 + vertex.position.x, 0.5 - vertex.position.y);
+#endif
+    let position = vec3<f32>(vertex.position.xy, vertex.position.z + sample_height(uv));
+
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    out.world_position = mesh_functions::mesh_position_local_to_world(world_from_local, vec4<f32>(position, 1.0));
+    out.position = position_world_to_clip(out.world_position.xyz);
+
+#ifdef PREPASS_PIPELINE
+#ifdef UNCLIPPED_DEPTH_ORTHO_EMULATION
+    out.unclipped_depth = out.position.z;
+    out.position.z = min(out.position.z, 1.0);
+#endif
+#ifdef NORMAL_PREPASS_OR_DEFERRED_PREPASS
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(sample_normal(uv), vertex.instance_index);
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_functions::mesh_tangent_local_to_world(world_from_local, vertex.tangent, vertex.instance_index);
+#endif
+#endif
+#ifdef MOTION_VECTOR_PREPASS
+    let previous_world_from_local = mesh_functions::get_previous_world_from_local(vertex.instance_index);
+    out.previous_world_position = mesh_functions::mesh_position_local_to_world(previous_world_from_local, vec4<f32>(position, 1.0));
+#endif
+#else
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(sample_normal(uv), vertex.instance_index);
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_functions::mesh_tangent_local_to_world(world_from_local, vertex.tangent, vertex.instance_index);
+#endif
+#endif
+
+#ifdef VERTEX_UVS_A
+    out.uv = vertex.uv;
+#endif
+#ifdef VERTEX_UVS_B
+    out.uv_b = vertex.uv_b;
+#endif
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    out.instance_index = vertex.instance_index;
+#endif
+#ifdef VISIBILITY_RANGE_DITHER
+    out.visibility_range_dither = mesh_functions::get_visibility_range_dither_level(
+        vertex.instance_index, world_from_local[3]);
+#endif
+
+    return out;
+}
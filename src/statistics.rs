@@ -0,0 +1,251 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::math::{Rect, UVec2};
+
+use crate::{HeightGrid, HeightMap};
+
+/// Summary of a set of heights.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{statistics::*, *};
+/// let ramp = ValueFunctionHeightMap(|p: Vec2| 0.25 + 0.5 * p.x);
+/// let stats = HeightStatistics::from_heightmap(&ramp, UVec2::new(65, 65));
+/// assert_eq!((stats.min, stats.max), (0., 0.5));
+/// assert!((stats.mean - 0.25).abs() < 1e-5);
+/// assert_eq!(stats.normalize(0.25), 0.5);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HeightStatistics {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub std_dev: f32,
+    /// Number of heights summarized.
+    pub count: usize,
+}
+
+impl HeightStatistics {
+    /// Statistics of the heights, ignoring NaNs. All zero if there are none.
+    pub fn from_values(heights: impl IntoIterator<Item = f32>) -> Self {
+        let mut stats = Self {
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            ..Self::default()
+        };
+        // Welford's algorithm, accumulated in f64 to stay precise over large grids.
+        let (mut mean, mut m2) = (0f64, 0f64);
+        for h in heights.into_iter().filter(|h| !h.is_nan()) {
+            stats.count += 1;
+            stats.min = stats.min.min(h);
+            stats.max = stats.max.max(h);
+            let delta = h as f64 - mean;
+            mean += delta / stats.count as f64;
+            m2 += delta * (h as f64 - mean);
+        }
+        if stats.count == 0 {
+            return Self::default();
+        }
+        stats.mean = mean as f32;
+        stats.std_dev = (m2 / stats.count as f64).sqrt() as f32;
+        stats
+    }
+
+    pub fn from_grid(grid: &HeightGrid) -> Self {
+        Self::from_values(grid.heights.iter().copied())
+    }
+
+    /// Statistics of `heightmap` sampled at a grid of `size`, like [`HeightGrid::from_heightmap`].
+    pub fn from_heightmap<H: HeightMap + ?Sized>(heightmap: &H, size: UVec2) -> Self {
+        Self::from_grid(&HeightGrid::from_heightmap(heightmap, size))
+    }
+
+    pub fn range(&self) -> f32 {
+        self.max - self.min
+    }
+
+    /// Maps a height from `[min, max]` to `[0, 1]`. Heights are mapped to 0 if all are equal.
+    pub fn normalize(&self, h: f32) -> f32 {
+        if self.range() > 0. {
+            (h - self.min) / self.range()
+        } else {
+            0.
+        }
+    }
+}
+
+/// Counts of values in equal width bins between `min` and `max`.
+/// Values outside of the range are counted in the first or last bin.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{statistics::*, *};
+/// let grid = HeightGrid::new(UVec2::new(4, 1), vec![0., 0.1, 0.6, 1.]);
+/// let histogram = grid.histogram(2);
+/// assert_eq!(histogram.counts, vec![2, 2]);
+/// assert_eq!(histogram.bin_range(1), (0.5, 1.));
+/// assert_eq!(grid.fraction_above(0.5), 0.5);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Histogram {
+    pub min: f32,
+    pub max: f32,
+    pub counts: Vec<u32>,
+}
+
+impl Histogram {
+    /// Panics if `bins` is zero.
+    pub fn new(values: impl IntoIterator<Item = f32>, bins: usize, min: f32, max: f32) -> Self {
+        assert!(bins > 0, "a histogram needs at least one bin");
+        let mut histogram = Self {
+            min,
+            max,
+            counts: vec![0; bins],
+        };
+        for value in values.into_iter().filter(|value| !value.is_nan()) {
+            let bin = histogram.bin(value);
+            histogram.counts[bin] += 1;
+        }
+        histogram
+    }
+
+    pub fn bin_width(&self) -> f32 {
+        (self.max - self.min) / self.counts.len() as f32
+    }
+
+    /// Bin counting `value`.
+    pub fn bin(&self, value: f32) -> usize {
+        let bin = ((value - self.min) / self.bin_width()).floor();
+        (bin.max(0.) as usize).min(self.counts.len() - 1)
+    }
+
+    /// Lower and upper bounds of a bin.
+    pub fn bin_range(&self, bin: usize) -> (f32, f32) {
+        let width = self.bin_width();
+        (
+            self.min + bin as f32 * width,
+            self.min + (bin + 1) as f32 * width,
+        )
+    }
+
+    /// Number of values counted.
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    /// Value below which a `fraction` of the counted values lie, interpolated within its bin.
+    pub fn quantile(&self, fraction: f32) -> f32 {
+        let target = fraction.clamp(0., 1.) * self.total() as f32;
+        let mut below = 0.;
+        for (bin, &count) in self.counts.iter().enumerate() {
+            let count = count as f32;
+            if count > 0. && below + count >= target {
+                let (min, max) = self.bin_range(bin);
+                return min + (max - min) * (target - below) / count;
+            }
+            below += count;
+        }
+        self.max
+    }
+}
+
+impl HeightGrid {
+    pub fn statistics(&self) -> HeightStatistics {
+        HeightStatistics::from_grid(self)
+    }
+
+    /// Histogram of the heights in `bins` bins between the lowest and highest height.
+    pub fn histogram(&self, bins: usize) -> Histogram {
+        let stats = self.statistics();
+        Histogram::new(self.heights.iter().copied(), bins, stats.min, stats.max)
+    }
+
+    /// Fraction of the grid points higher than `h`, out of those that are not holes.
+    pub fn fraction_above(&self, h: f32) -> f32 {
+        let (mut above, mut count) = (0, 0);
+        for &height in self.heights.iter().filter(|height| !height.is_nan()) {
+            count += 1;
+            above += (height > h) as usize;
+        }
+        if count == 0 {
+            return 0.;
+        }
+        above as f32 / count as f32
+    }
+
+    /// Slope angle in radians at each grid point, indexed like the heights, for a mesh spanning
+    /// `rect` with heights multiplied by `height_scale`. Uses central differences inside the grid
+    /// and one-sided differences on its edge. Grids a single point wide are flat across.
+    /// Slopes at and next to holes are NaN, and skipped by [`HeightGrid::slope_histogram`].
+    pub fn slopes(&self, rect: Rect, height_scale: f32) -> Vec<f32> {
+        let bounds = self.size.saturating_sub(UVec2::ONE);
+        let spacing = rect.size() / bounds.as_vec2();
+        let mut slopes = Vec::with_capacity(self.heights.len());
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let difference = |a: UVec2, b: UVec2, spacing: f32| {
+                    if a == b {
+                        return 0.;
+                    }
+                    let distance = (b.x - a.x + b.y - a.y) as f32 * spacing;
+                    height_scale * (self.get(b) - self.get(a)) / distance
+                };
+                let dx = difference(
+                    UVec2::new(x.saturating_sub(1), y),
+                    UVec2::new((x + 1).min(bounds.x), y),
+                    spacing.x,
+                );
+                let dy = difference(
+                    UVec2::new(x, y.saturating_sub(1)),
+                    UVec2::new(x, (y + 1).min(bounds.y)),
+                    spacing.y,
+                );
+                slopes.push(dx.hypot(dy).atan());
+            }
+        }
+        slopes
+    }
+
+    /// Histogram of [`HeightGrid::slopes`] in `bins` bins from flat to vertical.
+    pub fn slope_histogram(&self, rect: Rect, height_scale: f32, bins: usize) -> Histogram {
+        Histogram::new(self.slopes(rect, height_scale), bins, 0., FRAC_PI_2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn histogram_rejects_zero_bins() {
+        Histogram::new([0.5], 0, 0., 1.);
+    }
+
+    #[test]
+    fn slopes_of_single_point_wide_grids() {
+        let rect = Rect::from_center_size(Vec2::ZERO, Vec2::ONE);
+        // Heights rise by 1 over the unit length of the column.
+        let column = HeightGrid::new(UVec2::new(1, 3), vec![0., 0.5, 1.]);
+        for slope in column.slopes(rect, 1.) {
+            assert!((slope - FRAC_PI_2 / 2.).abs() < 1e-6);
+        }
+        let point = HeightGrid::new(UVec2::ONE, vec![1.]);
+        assert_eq!(point.slopes(rect, 1.), vec![0.]);
+        assert!(
+            HeightGrid::new(UVec2::ZERO, Vec::new())
+                .slopes(rect, 1.)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn fraction_above_ignores_holes() {
+        let grid = HeightGrid::new(UVec2::new(4, 1), vec![0., f32::NAN, 1., 2.]);
+        assert_eq!(grid.fraction_above(0.5), 2. / 3.);
+        assert_eq!(
+            HeightGrid::flat(UVec2::ONE, f32::NAN).fraction_above(0.),
+            0.
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::sync::Arc;
+
+use bevy::{
+    asset::Assets,
+    ecs::{
+        hierarchy::ChildOf,
+        prelude::{Commands, Component, Entity, Query, ResMut},
+    },
+    math::{IVec2, Rect, UVec2, Vec2, Vec3},
+    mesh::{Mesh, Mesh3d, VertexAttributeValues},
+    platform::collections::HashMap,
+    tasks::{AsyncComputeTaskPool, Task, TaskPool, futures::check_ready},
+    transform::components::{GlobalTransform, Transform},
+};
+
+use crate::{HeightMap, MeshBuilder, Orientation};
+
+/// Streams chunks of an unbounded procedural terrain around a tracked entity.
+///
+/// Chunks are spawned as children of the streamer's entity with a [`TerrainChunk`] component,
+/// and their meshes are generated on the [`AsyncComputeTaskPool`].
+/// The heightmap is sampled in the streamer's local ground plane coordinates, so chunk edges line up.
+/// Add materials to chunks by observing when [`TerrainChunk`] is added.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{streaming::*, *};
+/// fn setup(mut commands: Commands) {
+///     let player = commands.spawn(Transform::default()).id();
+///     commands.spawn(TerrainStreamer::new(
+///         ValueFunctionHeightMap(|p: Vec2| (p.x / 100.).sin() + (p.y / 100.).sin()),
+///         player,
+///     ));
+/// }
+/// ```
+#[derive(Component)]
+#[require(Transform)]
+pub struct TerrainStreamer {
+    /// Heightmap sampled in world units.
+    pub heightmap: Arc<dyn HeightMap + Send + Sync>,
+    /// Entity around which chunks are loaded.
+    pub target: Entity,
+    /// Width of a chunk in world units.
+    pub chunk_size: f32,
+    /// Number of vertices along each axis of a chunk.
+    pub chunk_resolution: UVec2,
+    /// Multiplier applied to sampled heights.
+    pub height_scale: f32,
+    /// Chunks within this many chunks of the target are loaded.
+    pub load_radius: u32,
+    /// Chunks further than this many chunks from the target are despawned.
+    /// Should be larger than `load_radius` to avoid thrashing at chunk borders.
+    pub unload_radius: u32,
+    /// Up axis of chunk meshes and of the streamer's local space.
+    pub orientation: Orientation,
+    chunks: HashMap<IVec2, Entity>,
+}
+
+impl TerrainStreamer {
+    pub fn new(heightmap: impl HeightMap + Send + Sync + 'static, target: Entity) -> Self {
+        Self {
+            heightmap: Arc::new(heightmap),
+            target,
+            chunk_size: 64.,
+            chunk_resolution: UVec2::splat(65),
+            height_scale: 1.,
+            load_radius: 4,
+            unload_radius: 6,
+            orientation: Orientation::ZUp,
+            chunks: HashMap::default(),
+        }
+    }
+
+    /// Chunk containing a position in the streamer's local space.
+    pub fn chunk_coord(&self, p: Vec2) -> IVec2 {
+        (p / self.chunk_size).floor().as_ivec2()
+    }
+
+    /// Center of a chunk in the streamer's local space, before orientation.
+    pub fn chunk_center(&self, coord: IVec2) -> Vec2 {
+        (coord.as_vec2() + Vec2::splat(0.5)) * self.chunk_size
+    }
+
+    /// Translation of a chunk relative to the streamer.
+    pub fn chunk_translation(&self, coord: IVec2) -> Vec3 {
+        Vec3::from(
+            self.orientation
+                .orient(self.chunk_center(coord).extend(0.).to_array()),
+        )
+    }
+
+    /// Entities of the currently spawned chunks.
+    pub fn chunks(&self) -> impl Iterator<Item = (IVec2, Entity)> + '_ {
+        self.chunks.iter().map(|(coord, entity)| (*coord, *entity))
+    }
+
+    /// Builds the mesh of a chunk, centered on the chunk's center.
+    ///
+    /// Normals are computed from the heightmap rather than the chunk's triangles,
+    /// so that they match across chunk borders.
+    /// Triangles touching a hole, where [`HeightMap::try_h`] is `None`, are left out.
+    pub fn build_chunk_mesh(&self, coord: IVec2) -> Mesh {
+        Self::build_chunk(
+            &self.heightmap,
+            self.chunk_center(coord),
+            self.chunk_size,
+            self.chunk_resolution,
+            self.height_scale,
+            self.orientation,
+        )
+    }
+
+    fn build_chunk(
+        heightmap: &Arc<dyn HeightMap + Send + Sync>,
+        center: Vec2,
+        chunk_size: f32,
+        resolution: UVec2,
+        height_scale: f32,
+        orientation: Orientation,
+    ) -> Mesh {
+        let chunk = ChunkHeightMap {
+            heightmap: heightmap.as_ref(),
+            center,
+            chunk_size,
+        };
+        let mut builder = MeshBuilder::grid_rect(
+            resolution,
+            Rect::from_center_size(Vec2::ZERO, Vec2::splat(chunk_size)),
+        );
+        builder.update_z_positions(&chunk);
+        for p in builder.positions.iter_mut() {
+            p[2] *= height_scale;
+        }
+        let mut mesh = builder.build();
+        let h = |p: Vec2| heightmap.try_h(p).unwrap_or(f32::NAN);
+        let spacing = chunk_size / (resolution - UVec2::ONE).as_vec2();
+        let normals: Vec<[f32; 3]> = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions
+                .iter()
+                .map(|p| {
+                    let p = center + Vec2::new(p[0], p[1]);
+                    let dx = Vec2::new(spacing.x, 0.);
+                    let dy = Vec2::new(0., spacing.y);
+                    let gradient = Vec2::new(h(p + dx) - h(p - dx), h(p + dy) - h(p - dy))
+                        * height_scale
+                        / (2. * spacing);
+                    // Next to holes the gradient is undefined.
+                    (-gradient)
+                        .extend(1.)
+                        .try_normalize()
+                        .unwrap_or(Vec3::Z)
+                        .to_array()
+                })
+                .collect(),
+            _ => return orientation.orient_mesh(mesh),
+        };
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        orientation.orient_mesh(mesh)
+    }
+
+    /// Spawns chunks near each streamer's target and despawns distant ones.
+    pub fn update(
+        mut streamers: Query<(Entity, &mut TerrainStreamer, &GlobalTransform)>,
+        targets: Query<&GlobalTransform>,
+        mut commands: Commands,
+    ) {
+        for (streamer_entity, mut streamer, streamer_transform) in streamers.iter_mut() {
+            let Ok(target_transform) = targets.get(streamer.target) else {
+                continue;
+            };
+            let local_target = streamer_transform
+                .affine()
+                .inverse()
+                .transform_point3(target_transform.translation());
+            let local_target = Vec3::from(streamer.orientation.unorient(local_target.to_array()));
+            let center = streamer.chunk_coord(local_target.truncate());
+
+            let unload_radius = streamer.unload_radius as i32;
+            streamer.chunks.retain(|coord, entity| {
+                let keep = (*coord - center).length_squared() <= unload_radius.pow(2);
+                if !keep {
+                    commands.entity(*entity).try_despawn();
+                }
+                keep
+            });
+
+            let load_radius = streamer.load_radius as i32;
+            for y in -load_radius..=load_radius {
+                for x in -load_radius..=load_radius {
+                    let offset = IVec2::new(x, y);
+                    let coord = center + offset;
+                    if offset.length_squared() > load_radius.pow(2)
+                        || streamer.chunks.contains_key(&coord)
+                    {
+                        continue;
+                    }
+                    let chunk_center = streamer.chunk_center(coord);
+                    let (heightmap, chunk_size, resolution, height_scale, orientation) = (
+                        streamer.heightmap.clone(),
+                        streamer.chunk_size,
+                        streamer.chunk_resolution,
+                        streamer.height_scale,
+                        streamer.orientation,
+                    );
+                    let task =
+                        AsyncComputeTaskPool::get_or_init(TaskPool::default).spawn(async move {
+                            Self::build_chunk(
+                                &heightmap,
+                                chunk_center,
+                                chunk_size,
+                                resolution,
+                                height_scale,
+                                orientation,
+                            )
+                        });
+                    let entity = commands
+                        .spawn((
+                            TerrainChunk { coord },
+                            TerrainChunkTask(task),
+                            Transform::from_translation(streamer.chunk_translation(coord)),
+                            ChildOf(streamer_entity),
+                        ))
+                        .id();
+                    streamer.chunks.insert(coord, entity);
+                }
+            }
+        }
+    }
+}
+
+/// Heightmap of a single chunk, mapping the chunk's heightmap space to the streamer's.
+struct ChunkHeightMap<'a> {
+    heightmap: &'a (dyn HeightMap + Send + Sync),
+    center: Vec2,
+    chunk_size: f32,
+}
+
+impl HeightMap for ChunkHeightMap<'_> {
+    fn h(&self, p: Vec2) -> f32 {
+        self.heightmap.h(self.center + p * self.chunk_size)
+    }
+
+    fn try_h(&self, p: Vec2) -> Option<f32> {
+        self.heightmap.try_h(self.center + p * self.chunk_size)
+    }
+}
+
+/// Chunk of terrain spawned by a [`TerrainStreamer`].
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Transform)]
+pub struct TerrainChunk {
+    pub coord: IVec2,
+}
+
+/// Mesh of a [`TerrainChunk`] being generated in the background.
+#[derive(Component)]
+pub struct TerrainChunkTask(pub Task<Mesh>);
+impl TerrainChunkTask {
+    /// Inserts meshes of chunks whose generation has finished.
+    pub fn update(
+        mut tasks: Query<(Entity, &mut TerrainChunkTask)>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut commands: Commands,
+    ) {
+        for (entity, mut task) in tasks.iter_mut() {
+            let Some(mesh) = check_ready(&mut task.0) else {
+                continue;
+            };
+            commands
+                .entity(entity)
+                .remove::<TerrainChunkTask>()
+                .insert(Mesh3d(meshes.add(mesh)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::mesh::Indices;
+
+    use super::*;
+
+    /// Flat ground with a round hole of radius 5 around `(32, 32)`.
+    struct Pit;
+    impl HeightMap for Pit {
+        fn h(&self, _: Vec2) -> f32 {
+            1.
+        }
+
+        fn try_h(&self, p: Vec2) -> Option<f32> {
+            (p.distance(Vec2::splat(32.)) >= 5.).then_some(1.)
+        }
+    }
+
+    #[test]
+    fn chunks_leave_out_holes() {
+        let streamer = TerrainStreamer::new(Pit, Entity::PLACEHOLDER);
+        assert_eq!(streamer.chunk_center(IVec2::ZERO), Vec2::splat(32.));
+        let mesh = streamer.build_chunk_mesh(IVec2::ZERO);
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("missing positions");
+        };
+        let Some(Indices::U32(indices)) = mesh.indices() else {
+            panic!("missing indices");
+        };
+        assert!(indices.len() < 64 * 64 * 6);
+        for &i in indices {
+            let p = positions[i as usize];
+            assert!(
+                Vec2::new(p[0], p[1]).length() >= 5.,
+                "{p:?} is over the hole"
+            );
+            assert_eq!(p[2], 1.);
+        }
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("missing normals");
+        };
+        assert!(normals.iter().flatten().all(|n| n.is_finite()));
+    }
+}
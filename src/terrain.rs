@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+#[cfg(feature = "pbr")]
+use bevy::pbr::{MeshMaterial3d, StandardMaterial};
+use bevy::{
+    asset::{AssetEvent, AssetId, Assets, Handle},
+    ecs::prelude::{
+        Commands, Component, DetectChanges, Entity, MessageReader, Query, Ref, Res, ResMut,
+    },
+    math::{Rect, UVec2, Vec2, Vec3},
+    mesh::{Mesh, Mesh3d},
+    platform::collections::HashSet,
+};
+
+use crate::{HeightGrid, HeightMap, MeshBuilder, Orientation, heightfield::Heightfield};
+
+/// Where a [`Terrain`] reads its heights from.
+#[derive(Clone)]
+pub enum TerrainSource {
+    /// Height grid asset, such as `"terrain.hmp.png#HeightGrid"`.
+    /// The mesh is rebuilt whenever the asset changes, including hot reloads.
+    Grid(Handle<HeightGrid>),
+    /// Procedural heightmap.
+    HeightMap(Arc<dyn HeightMap + Send + Sync>),
+}
+
+/// Terrain whose [`Mesh3d`] is built and kept up to date by the [`crate::HeightMapPlugin`].
+///
+/// The mesh is rebuilt when the component is mutated or its source grid changes,
+/// replacing the contents of the entity's mesh asset once it has one.
+/// With the `pbr` feature, the terrain's `material` is inserted as a `MeshMaterial3d`.
+/// Otherwise add a material alongside it to render the terrain.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::*;
+/// fn setup(asset_server: Res<AssetServer>, mut commands: Commands) {
+///     commands.spawn(
+///         Terrain::from_grid(asset_server.load("textures/terrain.hmp.png#HeightGrid"))
+///             .with_extents(Vec3::new(1024., 1024., 32.)),
+///     );
+///     commands.spawn(Terrain::from_heightmap(ValueFunctionHeightMap(|p: Vec2| p.x * p.y)));
+/// }
+/// ```
+#[derive(Component, Clone)]
+#[require(Mesh3d)]
+pub struct Terrain {
+    pub source: TerrainSource,
+    /// Number of vertices along each axis.
+    /// Defaults to the grid's size for [`TerrainSource::Grid`],
+    /// and [`Terrain::DEFAULT_RESOLUTION`] otherwise.
+    pub resolution: Option<UVec2>,
+    /// Size of the mesh along x and y, and the multiplier for heights.
+    /// Unlike a non-uniform `Transform` scale, normals are computed for the scaled mesh.
+    pub extents: Vec3,
+    /// Up axis of the mesh. `extents` are given before orientation, with heights along z.
+    pub orientation: Orientation,
+    #[cfg(feature = "pbr")]
+    pub material: Option<Handle<StandardMaterial>>,
+}
+
+impl Terrain {
+    pub const DEFAULT_RESOLUTION: UVec2 = UVec2::new(128, 128);
+
+    pub fn from_grid(grid: Handle<HeightGrid>) -> Self {
+        Self {
+            source: TerrainSource::Grid(grid),
+            resolution: None,
+            extents: Vec3::ONE,
+            orientation: Orientation::ZUp,
+            #[cfg(feature = "pbr")]
+            material: None,
+        }
+    }
+
+    pub fn from_heightmap(heightmap: impl HeightMap + Send + Sync + 'static) -> Self {
+        Self {
+            source: TerrainSource::HeightMap(Arc::new(heightmap)),
+            resolution: None,
+            extents: Vec3::ONE,
+            orientation: Orientation::ZUp,
+            #[cfg(feature = "pbr")]
+            material: None,
+        }
+    }
+
+    pub fn with_resolution(mut self, resolution: UVec2) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+
+    pub fn with_extents(mut self, extents: Vec3) -> Self {
+        self.extents = extents;
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    #[cfg(feature = "pbr")]
+    pub fn with_material(mut self, material: Handle<StandardMaterial>) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    /// Area covered by the mesh in the XY plane, before orientation.
+    pub fn rect(&self) -> Rect {
+        Rect::from_center_size(Vec2::ZERO, self.extents.truncate())
+    }
+
+    /// Builds the terrain mesh, or `None` if the source grid is not loaded yet.
+    pub fn build_mesh(&self, grids: &Assets<HeightGrid>) -> Option<Mesh> {
+        let resolution = self.resolution.unwrap_or(Self::DEFAULT_RESOLUTION);
+        let mut builder = match &self.source {
+            TerrainSource::Grid(handle) => {
+                let grid = grids.get(handle)?;
+                match self.resolution {
+                    Some(resolution) if resolution != grid.size => {
+                        let mut builder = MeshBuilder::grid_rect(resolution, self.rect());
+                        builder.update_z_positions(grid);
+                        builder
+                    }
+                    _ => grid.mesh_builder(self.rect()),
+                }
+            }
+            TerrainSource::HeightMap(heightmap) => {
+                let mut builder = MeshBuilder::grid_rect(resolution, self.rect());
+                builder.par_update_z_positions(heightmap.as_ref());
+                builder
+            }
+        };
+        // Scale heights before building so normals are computed for the scaled mesh.
+        for p in builder.positions.iter_mut() {
+            p[2] *= self.extents.z;
+        }
+        Some(builder.with_orientation(self.orientation).build())
+    }
+
+    /// Collider heightfield matching the terrain mesh, or `None` if the source grid is not loaded yet.
+    /// See [`Heightfield`] for the orientation of the collider.
+    pub fn heightfield(&self, grids: &Assets<HeightGrid>) -> Option<Heightfield> {
+        let rect = self.rect();
+        Some(match &self.source {
+            TerrainSource::Grid(handle) => {
+                let grid = grids.get(handle)?;
+                match self.resolution {
+                    Some(resolution) if resolution != grid.size => {
+                        Heightfield::from_heightmap(grid, resolution, rect, self.extents.z)
+                    }
+                    _ => Heightfield::from_grid(grid, rect, self.extents.z),
+                }
+            }
+            TerrainSource::HeightMap(heightmap) => Heightfield::from_heightmap(
+                heightmap.as_ref(),
+                self.resolution.unwrap_or(Self::DEFAULT_RESOLUTION),
+                rect,
+                self.extents.z,
+            ),
+        })
+    }
+
+    fn grid_id(&self) -> Option<AssetId<HeightGrid>> {
+        match &self.source {
+            TerrainSource::Grid(handle) => Some(handle.id()),
+            TerrainSource::HeightMap(_) => None,
+        }
+    }
+
+    /// Rebuilds meshes for terrains that changed or whose grid changed.
+    pub fn update(
+        mut grid_events: MessageReader<AssetEvent<HeightGrid>>,
+        terrains: Query<(Entity, Ref<Terrain>, &Mesh3d)>,
+        grids: Res<Assets<HeightGrid>>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut commands: Commands,
+    ) {
+        let changed_grids: HashSet<AssetId<HeightGrid>> = grid_events
+            .read()
+            .filter_map(|event| match event {
+                AssetEvent::Added { id }
+                | AssetEvent::Modified { id }
+                | AssetEvent::LoadedWithDependencies { id } => Some(*id),
+                _ => None,
+            })
+            .collect();
+        for (entity, terrain, mesh3d) in terrains.iter() {
+            let grid_changed = terrain
+                .grid_id()
+                .is_some_and(|id| changed_grids.contains(&id));
+            if !terrain.is_changed() && !grid_changed {
+                continue;
+            }
+            #[cfg(feature = "pbr")]
+            if terrain.is_changed()
+                && let Some(material) = &terrain.material
+            {
+                commands
+                    .entity(entity)
+                    .insert(MeshMaterial3d(material.clone()));
+            }
+            let Some(mesh) = terrain.build_mesh(&grids) else {
+                continue;
+            };
+            // The required `Mesh3d` starts out with the shared default handle, which is not replaced in place.
+            let id = mesh3d.id();
+            if id != AssetId::default()
+                && let Some(mut existing) = meshes.get_mut(id)
+            {
+                *existing = mesh;
+            } else {
+                commands.entity(entity).insert(Mesh3d(meshes.add(mesh)));
+            }
+        }
+    }
+}
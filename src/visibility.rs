@@ -0,0 +1,158 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::Image,
+    math::{UVec2, Vec3},
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::PlacedGrid;
+
+/// Line of sight and viewshed queries over a [`PlacedGrid`], with positions in world units.
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_heightmap::{visibility::*, *};
+/// // A wall along x = 0.
+/// let wall = ValueFunctionHeightMap(|p: Vec2| if p.x.abs() < 0.05 { 1. } else { 0. });
+/// let sight = LineOfSight::new(
+///     PlacedGrid::from_heightmap(&wall, UVec2::new(65, 65), Rect::new(-50., -50., 50., 50.))
+///         .with_height_scale(10.),
+/// );
+/// assert!(!sight.line_of_sight(Vec3::new(-20., 0., 2.), Vec3::new(20., 0., 2.)));
+/// assert!(sight.line_of_sight(Vec3::new(-20., 0., 2.), Vec3::new(-20., 30., 2.)));
+/// let fog: Image = sight.viewshed_image(Vec3::new(-20., 0., 0.), 2., 40.);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineOfSight {
+    pub terrain: PlacedGrid,
+}
+
+impl LineOfSight {
+    pub fn new(terrain: PlacedGrid) -> Self {
+        Self { terrain }
+    }
+
+    /// Whether the segment between two world positions clears the terrain.
+    pub fn line_of_sight(&self, from: Vec3, to: Vec3) -> bool {
+        let (from, to) = (self.terrain.unorient(from), self.terrain.unorient(to));
+        self.clear(from, to, self.tolerance())
+    }
+
+    /// Distance below the terrain a segment may pass and still clear it, to absorb rounding
+    /// in segments that graze the terrain, such as those ending on it. Scaled to the range of heights.
+    fn tolerance(&self) -> f32 {
+        let terrain = &self.terrain;
+        (1e-5 * terrain.height_scale * terrain.grid.statistics().range()).abs()
+    }
+
+    /// Whether the Z-up segment between `from` and `to` clears the terrain to within `tolerance`,
+    /// sampled at half the grid spacing and excluding its ends. Holes do not block it.
+    fn clear(&self, from: Vec3, to: Vec3, tolerance: f32) -> bool {
+        let spacing = self.terrain.spacing();
+        let distance = from.truncate().distance(to.truncate());
+        let steps = (2. * distance / spacing.min_element()).ceil() as u32;
+        (1..steps).all(|step| {
+            let p = from.lerp(to, step as f32 / steps as f32);
+            let terrain = self.terrain.height_at(p.truncate());
+            terrain.is_nan() || p.z >= terrain - tolerance
+        })
+    }
+
+    /// Visibility of each grid point, indexed like the grid's heights,
+    /// from an eye `observer_height` above the terrain at `observer` and within `max_range` of it.
+    /// Holes are never visible.
+    ///
+    /// Each point is tested with its own line of sight, so queries are linear in the number of
+    /// points in range times `max_range` over the grid spacing.
+    pub fn viewshed(&self, observer: Vec3, observer_height: f32, max_range: f32) -> Vec<bool> {
+        let terrain = &self.terrain;
+        let observer = terrain.unorient(observer).truncate();
+        let eye = observer.extend(terrain.height_at(observer) + observer_height);
+        let tolerance = self.tolerance();
+        let mut visible = vec![false; terrain.grid.heights.len()];
+        for y in 0..terrain.grid.size.y {
+            for x in 0..terrain.grid.size.x {
+                let xy = UVec2::new(x, y);
+                let target = terrain.plane_position(xy);
+                if target.distance(observer) > max_range || terrain.grid.get(xy).is_nan() {
+                    continue;
+                }
+                let target = target.extend(terrain.height_scale * terrain.grid.get(xy));
+                visible[terrain.grid.index(xy)] = self.clear(eye, target, tolerance);
+            }
+        }
+        visible
+    }
+
+    /// [`LineOfSight::viewshed`] as an `R8Unorm` mask, white where visible,
+    /// with the top row of the image at the top of the grid like heightmap images.
+    pub fn viewshed_image(&self, observer: Vec3, observer_height: f32, max_range: f32) -> Image {
+        let visible = self.viewshed(observer, observer_height, max_range);
+        let grid = &self.terrain.grid;
+        let size = grid.size;
+        let mut data = Vec::with_capacity(visible.len());
+        for y in (0..size.y).rev() {
+            for x in 0..size.x {
+                data.push(if visible[grid.index(UVec2::new(x, y))] {
+                    255
+                } else {
+                    0
+                });
+            }
+        }
+        Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::R8Unorm,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::{Rect, Vec2};
+
+    use super::*;
+    use crate::{HeightGrid, ValueFunctionHeightMap};
+
+    #[test]
+    fn segments_along_the_terrain_are_clear() {
+        let ramp = ValueFunctionHeightMap(|p: Vec2| 0.3 * p.x + 0.7 * p.y);
+        let sight = LineOfSight::new(
+            PlacedGrid::from_heightmap(&ramp, UVec2::new(33, 33), Rect::new(-7., -7., 7., 7.))
+                .with_height_scale(123.4),
+        );
+        let on_terrain = |p: Vec2| p.extend(sight.terrain.height_at(p));
+        for (from, to) in [
+            (Vec2::new(-6.3, -5.1), Vec2::new(5.7, 6.2)),
+            (Vec2::new(6.1, -4.9), Vec2::new(-5.3, 3.3)),
+        ] {
+            assert!(sight.line_of_sight(on_terrain(from), on_terrain(to)));
+            // Slightly below the terrain is still blocked.
+            let below = Vec3::new(0., 0., -0.01);
+            assert!(!sight.line_of_sight(on_terrain(from) + below, on_terrain(to) + below));
+        }
+    }
+
+    #[test]
+    fn holes_do_not_block_sight() {
+        // A wall along x = 0 with a hole through it.
+        let mut grid = HeightGrid::from_heightmap(
+            &ValueFunctionHeightMap(|p: Vec2| if p.x.abs() < 0.05 { 1. } else { 0. }),
+            UVec2::new(21, 21),
+        );
+        for y in 9..12 {
+            *grid.get_mut(UVec2::new(10, y)) = f32::NAN;
+        }
+        let sight = LineOfSight::new(PlacedGrid::new(grid, Rect::new(-10., -10., 10., 10.)));
+        assert!(sight.line_of_sight(Vec3::new(-5., 0., 0.5), Vec3::new(5., 0., 0.5)));
+        assert!(!sight.line_of_sight(Vec3::new(-5., 6., 0.5), Vec3::new(5., 6., 0.5)));
+        let visible = sight.viewshed(Vec3::new(-5., 0., 0.), 0.5, 20.);
+        assert!(!visible[sight.terrain.grid.index(UVec2::new(10, 10))]);
+    }
+}